@@ -1,22 +1,57 @@
-use crate::model::sector::MissionType;
+use crate::model::mission_report::MissionReport;
+use crate::model::outpost::{
+    MissionPreparationIssue, ModuleRepairIssue, OutpostForecast, FORECAST_TURNS,
+};
+use crate::model::profile::{Profile, Unlock};
+use crate::model::run::Objective;
+use crate::model::sector::{Coordinates, Mission, MissionType, SubSector, Visibility};
+use crate::model::ship::ShipClass;
+use crate::model::threats::MAX_PROTECTION;
 use crate::model::Entity;
 use crate::model::{
-    crew::CrewMember, modules::Module, resources::Resources, sector::SectorType, Game,
+    crew::CrewMember,
+    difficulty::Difficulty,
+    game_state::{GameState, SeedIssue, HISTORY_TURNS},
+    modules::Module,
+    resources::{ResourceKind, ResourceType, Resources},
+    scenario::{Scenario, ScenarioIssue, SCENARIO_DIR},
+    sector::SectorType,
+    Game, GameSetup,
 };
 use catppuccin::{Colour, Flavour};
 use crossterm::event::KeyCode;
 use std::io;
+use std::path::Path;
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table, Wrap},
+    widgets::{
+        Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Sparkline, Table, Wrap,
+    },
     Frame,
 };
 
+/// the profile outlives every run and is kept apart from the save game
+const PROFILE_PATH: &str = "./profile.json";
+
 pub struct App {
     pub game: Game,
+    pub profile: Profile,
+    /// the choices for the next new game
+    pub setup: GameSetup,
+    pub scenarios: Vec<Scenario>,
+    /// scenario files that could not be loaded and why
+    pub scenario_issues: Vec<(String, ScenarioIssue)>,
+    /// what has been typed or pasted while entering a seed
+    pub seed_input: String,
+    pub seed_issue: Option<SeedIssue>,
+    /// why the last crew member could not join the mission, until the next key press
+    pub mission_issue: Option<MissionPreparationIssue>,
+    pub repair_issue: Option<ModuleRepairIssue>,
+    /// the outlook of the alerts, played out after each action rather than each frame
+    pub forecast: OutpostForecast,
     pub palette: Flavour,
 
     pub state: Vec<State>,
@@ -29,6 +64,7 @@ pub enum State {
     // module states
     Modules(usize),
     AssignCrew(usize, usize),
+    BuildShip(usize),
     // crew states
     Crew(usize),
     AssignToModule(usize, usize),
@@ -37,8 +73,14 @@ pub enum State {
     // sector states
     Sector(i32, i32),
     SelectMission(i32, i32, usize),
-    MissionPreparation(i32, i32, usize),
+    MissionPreparation(i32, i32, usize, usize),
     AssignCrewToMission(usize),
+    MissionEvent(usize),
+    Threat(usize),
+    MissionArchive(usize),
+    RunSummary,
+    NewGame(usize),
+    EnterSeed,
 }
 
 impl State {
@@ -48,7 +90,27 @@ impl State {
         use State::*;
         use StateTransition::*;
         match *self {
-            GameMenu => vec![PopState(Esc), QuitAndSave(Char('q'))],
+            GameMenu => vec![
+                PopState(Esc),
+                QuitAndSave(Char('q')),
+                PushState(Char('n'), NewGame(0)),
+            ],
+            RunSummary => vec![QuitAndSave(Char('q')), PushState(Enter, NewGame(0))],
+            NewGame(i) => vec![
+                PopState(Esc),
+                ReplaceState(Char('j'), NewGame(circular_index((i as i32) + 1, 3))),
+                ReplaceState(Char('k'), NewGame(circular_index((i as i32) - 1, 3))),
+                ApplyDomainEvent(Char('l'), NextSetupOption),
+                ApplyDomainEvent(Char('h'), PreviousSetupOption),
+                ApplyDomainEvent(Enter, StartNewGame),
+                PushState(Char('p'), EnterSeed),
+            ],
+            // every other key is typed into the seed
+            EnterSeed => vec![
+                PopState(Esc),
+                ApplyDomainEvent(Backspace, DeleteSeedCharacter),
+                ApplyDomainEvent(Enter, ApplySeed),
+            ],
             Overview => vec![
                 PushState(Esc, GameMenu),
                 PushState(Char('c'), Crew(0)),
@@ -80,7 +142,23 @@ impl State {
                 ),
                 ApplyDomainEvent(Char('+'), IncrementModuleEnergyLevel),
                 ApplyDomainEvent(Char('-'), DecrementModuleEnergyLevel),
+                ApplyDomainEvent(Char('>'), RaiseModulePriority),
+                ApplyDomainEvent(Char('<'), LowerModulePriority),
+                ApplyDomainEvent(Char('f'), RepairModule),
                 PushState(Char('a'), AssignCrew(0, i)),
+                PushState(Char('b'), BuildShip(0)),
+            ],
+            BuildShip(c) => vec![
+                PopState(Esc),
+                ReplaceState(
+                    Char('j'),
+                    BuildShip(circular_index((c as i32) + 1, ShipClass::ALL.len())),
+                ),
+                ReplaceState(
+                    Char('k'),
+                    BuildShip(circular_index((c as i32) - 1, ShipClass::ALL.len())),
+                ),
+                ApplyDomainEvent(Enter, BuildSelectedShip),
             ],
             Crew(i) => vec![
                 PopState(Esc),
@@ -131,27 +209,38 @@ impl State {
                     Sector(x, clamp(y - 1, app.game.sector.bounds_at_x(x))),
                 ),
                 PushState(Enter, SelectMission(x, y, 0)),
+                PushState(Char('e'), MissionEvent(0)),
+                PushState(Char('a'), MissionArchive(0)),
             ],
-            SelectMission(x, y, m) => vec![
+            MissionArchive(r) => vec![
                 PopState(Esc),
                 ReplaceState(
                     Char('j'),
-                    SelectMission(
-                        x,
-                        y,
-                        circular_index((m as i32) + 1, app.game.sector.missions_at(x, y).len()),
-                    ),
+                    MissionArchive(circular_index((r as i32) + 1, app.game.archive.len())),
                 ),
                 ReplaceState(
                     Char('k'),
-                    SelectMission(
-                        x,
-                        y,
-                        circular_index((m as i32) - 1, app.game.sector.missions_at(x, y).len()),
-                    ),
+                    MissionArchive(circular_index((r as i32) - 1, app.game.archive.len())),
                 ),
-                PushState(Enter, MissionPreparation(x, y, m)),
             ],
+            SelectMission(x, y, m) => {
+                let missions_len = app.game.sector.missions_at(x, y).len();
+                let mut transitions = vec![
+                    PopState(Esc),
+                    ReplaceState(
+                        Char('j'),
+                        SelectMission(x, y, circular_index((m as i32) + 1, missions_len)),
+                    ),
+                    ReplaceState(
+                        Char('k'),
+                        SelectMission(x, y, circular_index((m as i32) - 1, missions_len)),
+                    ),
+                ];
+                if missions_len > 0 {
+                    transitions.push(PushState(Enter, MissionPreparation(x, y, m, 0)));
+                }
+                transitions
+            }
             AssignToModule(c, m) => vec![
                 PopState(Esc),
                 ReplaceState(
@@ -188,13 +277,68 @@ impl State {
                 ),
                 ApplyDomainEvent(Enter, AssignCrewMemberToModule),
             ],
-            MissionPreparation(_, _, _) => vec![
-                PopState(Esc),
-                ApplyDomainEvent(Enter, StartMission),
-                PushState(Char('a'), AssignCrewToMission(0)),
-                ApplyDomainEvent(Char('+'), IncrementPrepareForTurns),
-                ApplyDomainEvent(Char('-'), DecrementPrepareForTurns),
-            ],
+            MissionPreparation(x, y, m, c) => {
+                let crew_len = app.game.outpost.describe_mission_preparation_crew().len();
+                vec![
+                    PopState(Esc),
+                    ApplyDomainEvent(Enter, StartMission),
+                    PushState(Char('a'), AssignCrewToMission(0)),
+                    ApplyDomainEvent(Char('+'), IncrementPrepareForTurns),
+                    ApplyDomainEvent(Char('-'), DecrementPrepareForTurns),
+                    ReplaceState(
+                        Char('j'),
+                        MissionPreparation(x, y, m, circular_index((c as i32) + 1, crew_len)),
+                    ),
+                    ReplaceState(
+                        Char('k'),
+                        MissionPreparation(x, y, m, circular_index((c as i32) - 1, crew_len)),
+                    ),
+                    ApplyDomainEvent(Char('d'), RemoveCrewMemberFromMission),
+                    ApplyDomainEvent(Char('s'), SelectNextShipForMission),
+                    ApplyDomainEvent(Char('J'), MoveCrewMemberDownInMission),
+                    ApplyDomainEvent(Char('K'), MoveCrewMemberUpInMission),
+                ]
+            }
+            MissionEvent(o) => {
+                let options_len = app
+                    .game
+                    .sector
+                    .pending_mission_event()
+                    .map(|e| e.options().len())
+                    .unwrap_or(0);
+                vec![
+                    PopState(Esc),
+                    ReplaceState(
+                        Char('j'),
+                        MissionEvent(circular_index((o as i32) + 1, options_len)),
+                    ),
+                    ReplaceState(
+                        Char('k'),
+                        MissionEvent(circular_index((o as i32) - 1, options_len)),
+                    ),
+                    ApplyDomainEvent(Enter, ResolveMissionEvent),
+                ]
+            }
+            // a crisis cannot be dismissed without a response
+            Threat(o) => {
+                let options_len = app
+                    .game
+                    .outpost
+                    .pending_threat()
+                    .map(|t| t.options().len())
+                    .unwrap_or(0);
+                vec![
+                    ReplaceState(
+                        Char('j'),
+                        Threat(circular_index((o as i32) + 1, options_len)),
+                    ),
+                    ReplaceState(
+                        Char('k'),
+                        Threat(circular_index((o as i32) - 1, options_len)),
+                    ),
+                    ApplyDomainEvent(Enter, ResolveThreat),
+                ]
+            }
             AssignCrewToMission(c) => vec![
                 PopState(Esc),
                 ReplaceState(
@@ -229,8 +373,15 @@ impl std::string::ToString for State {
             SelectMission(_, _, _) => String::from("Select Mission"),
             Research => String::from("Research"),
             AssignToModule(_, _) | AssignCrew(_, _) => String::from("Assign Crew Member to Module"),
-            MissionPreparation(_, _, _) => String::from("Prepare Mission"),
+            BuildShip(_) => String::from("Build Ship"),
+            MissionPreparation(_, _, _, _) => String::from("Prepare Mission"),
             AssignCrewToMission(_) => String::from("Assign Crew Member to Mission"),
+            MissionEvent(_) => String::from("Mission Event"),
+            Threat(_) => String::from("Crisis"),
+            MissionArchive(_) => String::from("Mission Archive"),
+            RunSummary => String::from("End of Run"),
+            NewGame(_) => String::from("New Game"),
+            EnterSeed => String::from("Enter Seed"),
         }
     }
 }
@@ -248,34 +399,83 @@ enum StateTransition {
 enum DomainEvent {
     IncrementModuleEnergyLevel,
     DecrementModuleEnergyLevel,
+    RaiseModulePriority,
+    LowerModulePriority,
+    RepairModule,
     AssignCrewMemberToModule,
     FinishTurn,
     StartMission,
     IncrementPrepareForTurns,
     DecrementPrepareForTurns,
     AssignCrewMemberToMission,
+    RemoveCrewMemberFromMission,
+    MoveCrewMemberUpInMission,
+    MoveCrewMemberDownInMission,
+    SelectNextShipForMission,
+    BuildSelectedShip,
+    ResolveMissionEvent,
+    ResolveThreat,
+    NextSetupOption,
+    PreviousSetupOption,
+    StartNewGame,
+    DeleteSeedCharacter,
+    ApplySeed,
 }
 
 impl App {
     pub fn new() -> App {
         let input_path = "./saves/current.json";
 
-        let game: Game = std::fs::File::open(input_path)
+        let profile: Profile = std::fs::File::open(PROFILE_PATH)
             .ok()
             .and_then(|data| serde_json::from_reader(data).ok())
-            .unwrap_or_else(Game::new);
+            .unwrap_or_default();
+
+        let (scenarios, scenario_issues) = Scenario::load_all(Path::new(SCENARIO_DIR));
+        let setup = GameSetup::random();
+        let mut state = vec![State::Overview];
+        let game: Game = match std::fs::File::open(input_path)
+            .ok()
+            .and_then(|data| serde_json::from_reader(data).ok())
+        {
+            Some(game) => game,
+            None => {
+                state.push(State::NewGame(0));
+                Game::new(&setup, &profile)
+            }
+        };
+        if game.outcome.is_some() {
+            state.push(State::RunSummary);
+        }
+        let forecast = game.outpost.forecast(FORECAST_TURNS);
 
         App {
             game,
+            profile,
+            setup,
+            scenarios,
+            scenario_issues,
+            seed_input: String::new(),
+            seed_issue: None,
+            mission_issue: None,
+            repair_issue: None,
+            forecast,
             palette: Flavour::Mocha,
-            state: vec![State::Overview],
+            state,
         }
     }
 
+    fn save_profile(&self) {
+        let data = serde_json::to_string(&self.profile).unwrap();
+        let _ = std::fs::write(PROFILE_PATH, data);
+    }
+
     pub fn input(&mut self, code: KeyCode) -> Option<io::Result<()>> {
         use DomainEvent::*;
         use State::*;
         use StateTransition::*;
+        self.mission_issue = None;
+        self.repair_issue = None;
         let transitions = self.current_state().transitions(self);
         let transition = transitions.iter().find(|t| match t {
             PopState(c)
@@ -324,6 +524,26 @@ impl App {
                             }
                             _ => (),
                         },
+                        RaiseModulePriority => match self.current_state() {
+                            State::Modules(i) => {
+                                self.game.raise_module_priority(*i);
+                            }
+                            _ => (),
+                        },
+                        LowerModulePriority => match self.current_state() {
+                            State::Modules(i) => {
+                                self.game.lower_module_priority(*i);
+                            }
+                            _ => (),
+                        },
+                        RepairModule => match self.current_state() {
+                            State::Modules(i) => {
+                                if let Err(issue) = self.game.repair_module(*i) {
+                                    self.repair_issue = Some(issue);
+                                }
+                            }
+                            _ => (),
+                        },
                         AssignCrewMemberToModule => match self.current_state() {
                             AssignToModule(c, m) | AssignCrew(c, m) => {
                                 self.game.assign_crew_member_to_module(*c, *m);
@@ -331,26 +551,146 @@ impl App {
                             }
                             _ => (),
                         },
-                        FinishTurn => self.game.finish_turn(),
+                        FinishTurn => {
+                            self.game.finish_turn();
+                            if self.game.sector.pending_mission_event().is_some() {
+                                self.state.push(MissionEvent(0));
+                            }
+                            if self.game.outpost.pending_threat().is_some() {
+                                self.state.push(Threat(0));
+                            }
+                            if let Some(summary) = self.game.summary() {
+                                let seed = self.game.state.seed.clone();
+                                for unlock in self.profile.record_run(&seed, &summary) {
+                                    self.game.state.log(&format!("unlocked {}", unlock.name()));
+                                }
+                                self.save_profile();
+                                self.state.push(RunSummary);
+                            }
+                        }
+                        NextSetupOption | PreviousSetupOption => match *self.current_state() {
+                            NewGame(field) => {
+                                let offset = match e {
+                                    NextSetupOption => 1,
+                                    _ => -1,
+                                };
+                                let setup = &mut self.setup;
+                                match field {
+                                    0 => setup.reroll_seed(),
+                                    1 => {
+                                        let i = Difficulty::ALL
+                                            .iter()
+                                            .position(|d| *d == setup.difficulty)
+                                            .unwrap();
+                                        setup.difficulty = Difficulty::ALL[circular_index(
+                                            i as i32 + offset,
+                                            Difficulty::ALL.len(),
+                                        )];
+                                    }
+                                    _ => {
+                                        let i = self
+                                            .scenarios
+                                            .iter()
+                                            .position(|s| s.name == setup.scenario.name)
+                                            .unwrap_or(0);
+                                        setup.scenario = self.scenarios[circular_index(
+                                            i as i32 + offset,
+                                            self.scenarios.len(),
+                                        )]
+                                        .clone();
+                                    }
+                                }
+                            }
+                            _ => (),
+                        },
+                        StartNewGame => {
+                            self.game = Game::new(&self.setup, &self.profile);
+                            self.setup.reroll_seed();
+                            self.state = vec![Overview];
+                        }
+                        DeleteSeedCharacter => {
+                            self.seed_input.pop();
+                            self.seed_issue = None;
+                        }
+                        ApplySeed => match self.setup.set_seed(&self.seed_input) {
+                            Ok(()) => {
+                                self.seed_input.clear();
+                                self.seed_issue = None;
+                                self.state.pop();
+                            }
+                            Err(issue) => self.seed_issue = Some(issue),
+                        },
+                        ResolveThreat => match *self.current_state() {
+                            Threat(o) => {
+                                self.game.resolve_threat(o);
+                                self.state.pop();
+                            }
+                            _ => (),
+                        },
+                        ResolveMissionEvent => match self.current_state() {
+                            MissionEvent(o) => {
+                                self.game.resolve_mission_event(*o);
+                                self.state.pop();
+                            }
+                            _ => (),
+                        },
                         IncrementPrepareForTurns => self.game.increment_prepare_for_turns(),
                         DecrementPrepareForTurns => self.game.decrement_prepare_for_turns(),
-                        StartMission => match self.current_state() {
-                            MissionPreparation(x, y, m) => {
-                                let started = self.game.start_mission(*x, *y, *m);
-                                if started {
+                        StartMission => match *self.current_state() {
+                            MissionPreparation(x, y, m, _) => {
+                                if self.game.start_mission(x, y, m).is_ok() {
                                     self.state.pop();
                                 }
                             }
                             _ => (),
                         },
-                        AssignCrewMemberToMission => match self.current_state() {
+                        AssignCrewMemberToMission => match *self.current_state() {
                             AssignCrewToMission(c) => {
-                                self.game.prepare_crew_member_for_mission(*c);
+                                match self.game.prepare_crew_member_for_mission(c) {
+                                    Ok(()) => {
+                                        self.state.pop();
+                                    }
+                                    Err(issue) => self.mission_issue = Some(issue),
+                                }
+                            }
+                            _ => (),
+                        },
+                        SelectNextShipForMission => self.game.select_next_ship_for_mission(),
+                        BuildSelectedShip => match *self.current_state() {
+                            BuildShip(c) => {
+                                if self.game.build_ship(c).is_ok() {
+                                    self.state.pop();
+                                }
+                            }
+                            _ => (),
+                        },
+                        RemoveCrewMemberFromMission => match *self.current_state() {
+                            MissionPreparation(x, y, m, c) => {
+                                self.game.remove_crew_member_from_mission(c);
+                                let crew_len =
+                                    self.game.outpost.describe_mission_preparation_crew().len();
+                                let c = std::cmp::min(c, crew_len.saturating_sub(1));
                                 self.state.pop();
+                                self.state.push(MissionPreparation(x, y, m, c));
                             }
                             _ => (),
                         },
+                        MoveCrewMemberUpInMission | MoveCrewMemberDownInMission => {
+                            match *self.current_state() {
+                                MissionPreparation(x, y, m, c) => {
+                                    let offset = match e {
+                                        MoveCrewMemberUpInMission => -1,
+                                        _ => 1,
+                                    };
+                                    let c = self.game.move_crew_member_in_mission(c, offset);
+                                    self.state.pop();
+                                    self.state.push(MissionPreparation(x, y, m, c));
+                                }
+                                _ => (),
+                            }
+                        }
                     }
+                    self.forecast = self.game.outpost.forecast(FORECAST_TURNS);
                     if self.state.is_empty() {
                         Some(Ok(()))
                     } else {
@@ -358,7 +698,13 @@ impl App {
                     }
                 }
             },
-            None => None,
+            None => {
+                if let (EnterSeed, KeyCode::Char(c)) = (self.current_state(), code) {
+                    self.seed_input.push(c);
+                    self.seed_issue = None;
+                }
+                None
+            }
         }
     }
 
@@ -415,57 +761,43 @@ impl App {
         let consumption = self.game.outpost.consumption() + self.game.outpost.crew_upkeep();
         let production = self.game.outpost.production();
         let resources = self.game.outpost.resources();
-        let text = vec![Spans::from(vec![
-            Span::styled(
-                format!("turn {}", self.game.state.current_turn),
-                Style::default().fg(to_color(self.palette.text())),
-            ),
-            Span::raw(" | "),
-            Span::styled(
-                format!(
-                    "{}/{}",
-                    consumption.energy.to_string(),
-                    production.energy.to_string(),
-                ),
-                Style::default().fg(to_color(self.palette.yellow())),
-            ),
-            Span::raw(" | "),
-            Span::styled(
-                format!(
-                    "{}/{}",
-                    consumption.living_space.to_string(),
-                    production.living_space.to_string(),
-                ),
-                Style::default().fg(to_color(self.palette.peach())),
-            ),
-            Span::raw(" | "),
-            Span::styled(
-                format!(
-                    "{}({})",
-                    resources.minerals.to_string(),
-                    print_i32(production.minerals - consumption.minerals),
-                ),
-                Style::default().fg(to_color(self.palette.sapphire())),
-            ),
-            Span::raw(" | "),
-            Span::styled(
-                format!(
-                    "{}({})",
-                    resources.food.to_string(),
-                    print_i32(production.food - consumption.food),
-                ),
-                Style::default().fg(to_color(self.palette.green())),
-            ),
-            Span::raw(" | "),
-            Span::styled(
-                format!(
-                    "{}({})",
-                    resources.water.to_string(),
-                    print_i32(production.water - consumption.water),
+        let storage = self.game.outpost.storage();
+        let mut line = vec![Span::styled(
+            format!("turn {}", self.game.state.current_turn),
+            Style::default().fg(to_color(self.palette.text())),
+        )];
+        for resource_type in ResourceType::ALL {
+            let metadata = resource_type.metadata();
+            let value = match metadata.kind {
+                ResourceKind::Flow => {
+                    let mut value = format!(
+                        "{}/{}",
+                        consumption[resource_type].to_string(),
+                        production[resource_type].to_string(),
+                    );
+                    if resource_type == ResourceType::Energy {
+                        value.push_str(&format!(
+                            " [{}/{}]",
+                            self.game.outpost.energy_charge().to_string(),
+                            storage[resource_type].to_string(),
+                        ));
+                    }
+                    value
+                }
+                ResourceKind::Stock => format!(
+                    "{}/{}({})",
+                    resources[resource_type].to_string(),
+                    storage[resource_type].to_string(),
+                    print_i32(production[resource_type] - consumption[resource_type]),
                 ),
-                Style::default().fg(to_color(self.palette.blue())),
-            ),
-        ])];
+            };
+            line.push(Span::raw(" | "));
+            line.push(Span::styled(
+                value,
+                Style::default().fg(to_color((metadata.colour)(self.palette))),
+            ));
+        }
+        let text = vec![Spans::from(line)];
 
         f.render_widget(Paragraph::new(text).alignment(Alignment::Center), area)
     }
@@ -499,13 +831,37 @@ impl App {
             .modules()
             .iter()
             .map(|m| {
-                ListItem::new(Spans::from(vec![Span::styled(
+                let mut spans = vec![Span::styled(
                     m.name(),
                     Style::default().fg(to_color(self.palette.text())),
-                )]))
+                )];
+                if self.game.outpost.module_condition(m.id()) <= 0 {
+                    spans.push(Span::styled(
+                        " (broken)",
+                        Style::default().fg(to_color(self.palette.red())),
+                    ));
+                }
+                ListItem::new(Spans::from(spans))
             })
             .collect();
 
+        let area = match &self.repair_issue {
+            Some(issue) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+                    .split(area);
+                f.render_widget(
+                    Paragraph::new(Span::styled(
+                        issue.to_string(),
+                        Style::default().fg(to_color(self.palette.red())),
+                    )),
+                    chunks[1],
+                );
+                chunks[0]
+            }
+            None => area,
+        };
         f.render_stateful_widget(
             List::new(modules)
                 .block(self.border("Modules (m)", focused))
@@ -551,6 +907,23 @@ impl App {
             })
             .collect();
 
+        let area = match &self.repair_issue {
+            Some(issue) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+                    .split(area);
+                f.render_widget(
+                    Paragraph::new(Span::styled(
+                        issue.to_string(),
+                        Style::default().fg(to_color(self.palette.red())),
+                    )),
+                    chunks[1],
+                );
+                chunks[0]
+            }
+            None => area,
+        };
         f.render_stateful_widget(
             List::new(modules)
                 .block(self.border("Assign To Module", focused))
@@ -627,13 +1000,38 @@ impl App {
             .crew()
             .iter()
             .map(|c| {
-                ListItem::new(Spans::from(vec![Span::styled(
-                    c.name(),
-                    Style::default().fg(to_color(self.palette.text())),
-                )]))
+                let item = if self.game.outpost.is_prepared_for_mission(c.id()) {
+                    Span::styled(
+                        format!("{} (prepared)", c.name()),
+                        Style::default().fg(to_color(self.palette.subtext0())),
+                    )
+                } else {
+                    Span::styled(
+                        c.name().clone(),
+                        Style::default().fg(to_color(self.palette.text())),
+                    )
+                };
+                ListItem::new(Spans::from(vec![item]))
             })
             .collect();
 
+        let area = match &self.mission_issue {
+            Some(issue) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+                    .split(area);
+                f.render_widget(
+                    Paragraph::new(Span::styled(
+                        issue.to_string(),
+                        Style::default().fg(to_color(self.palette.red())),
+                    )),
+                    chunks[1],
+                );
+                chunks[0]
+            }
+            None => area,
+        };
         f.render_stateful_widget(
             List::new(crew)
                 .block(self.border("Assign To Mission", focused))
@@ -686,6 +1084,310 @@ impl App {
         )
     }
 
+    /// the dashboard of the outpost
+    fn overview<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        use Constraint::*;
+        use Direction::*;
+
+        let rows = Layout::default()
+            .direction(Vertical)
+            .constraints([Length(7), Min(0)].as_ref())
+            .split(area);
+        let columns = Layout::default()
+            .direction(Horizontal)
+            .constraints([Percentage(50), Percentage(50)].as_ref())
+            .split(rows[1]);
+        let left = Layout::default()
+            .direction(Vertical)
+            .constraints([Percentage(50), Percentage(50)].as_ref())
+            .split(columns[0]);
+        let right = Layout::default()
+            .direction(Vertical)
+            .constraints([Percentage(50), Percentage(50)].as_ref())
+            .split(columns[1]);
+
+        self.overview_charts(f, rows[0]);
+        self.overview_crew(f, left[0]);
+        self.overview_modules(f, left[1]);
+        self.overview_status(f, right[0]);
+        self.overview_alerts(f, right[1]);
+    }
+
+    fn overview_charts<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        use Constraint::*;
+        use Direction::*;
+
+        let history = &self.game.state.history;
+        let outpost = &self.game.outpost;
+        let storage = outpost.storage();
+        let mut charts: Vec<(String, Vec<u64>, u64, Colour)> = vec![];
+        for resource_type in ResourceType::ALL.into_iter().filter(|t| t.is_stock()) {
+            let metadata = resource_type.metadata();
+            charts.push((
+                format!(
+                    "{} {}/{}",
+                    metadata.name,
+                    outpost.resources()[resource_type],
+                    storage[resource_type]
+                ),
+                history
+                    .iter()
+                    .map(|r| r.resources[resource_type].max(0) as u64)
+                    .collect(),
+                storage[resource_type].max(1) as u64,
+                (metadata.colour)(self.palette),
+            ));
+        }
+        charts.push((
+            format!(
+                "charge {}/{}",
+                outpost.energy_charge(),
+                storage[ResourceType::Energy]
+            ),
+            history
+                .iter()
+                .map(|r| r.energy_charge.max(0) as u64)
+                .collect(),
+            storage[ResourceType::Energy].max(1) as u64,
+            (ResourceType::Energy.metadata().colour)(self.palette),
+        ));
+        charts.push((
+            String::from("crew health"),
+            history
+                .iter()
+                .map(|r| r.crew.health.max(0) as u64)
+                .collect(),
+            100,
+            self.palette.green(),
+        ));
+
+        let constraints: Vec<Constraint> = charts
+            .iter()
+            .map(|_| Ratio(1, charts.len() as u32))
+            .collect();
+        let chunks = Layout::default()
+            .direction(Horizontal)
+            .constraints(constraints)
+            .split(area);
+        for ((title, data, max, colour), chunk) in charts.iter().zip(chunks) {
+            // the sparkline draws from the start, keep the turns that fit
+            let width = chunk.width.saturating_sub(2) as usize;
+            let data = &data[data.len().saturating_sub(width)..];
+            f.render_widget(
+                Sparkline::default()
+                    .block(self.border(title, false))
+                    .data(data)
+                    .max(*max)
+                    .style(Style::default().fg(to_color(*colour))),
+                chunk,
+            );
+        }
+    }
+
+    fn overview_crew<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let flag = |is_set: bool, label: &'static str| {
+            if is_set {
+                Span::styled(label, Style::default().fg(to_color(self.palette.red())))
+            } else {
+                Span::styled(
+                    label,
+                    Style::default().fg(to_color(self.palette.overlay0())),
+                )
+            }
+        };
+        let mut crew = self.game.outpost.crew();
+        crew.sort_by(|a, b| a.name().cmp(b.name()));
+        let lines: Vec<Spans> = crew
+            .iter()
+            .map(|c| {
+                let health_colour = if c.health() > 60 {
+                    self.palette.green()
+                } else if c.health() > 20 {
+                    self.palette.yellow()
+                } else {
+                    self.palette.red()
+                };
+                Spans::from(vec![
+                    Span::raw(format!("{:<10}", c.name())),
+                    Span::styled(
+                        format!("{:>5} ", print_percentage(c.health())),
+                        Style::default().fg(to_color(health_colour)),
+                    ),
+                    flag(c.is_hungry(), "hungry "),
+                    flag(c.is_thirsty(), "thirsty "),
+                    flag(c.is_tired(), "tired"),
+                ])
+            })
+            .collect();
+        f.render_widget(
+            Paragraph::new(lines).block(self.border("Crew Status", false)),
+            area,
+        )
+    }
+
+    fn overview_modules<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let outpost = &self.game.outpost;
+        let lines: Vec<Spans> = outpost
+            .modules()
+            .iter()
+            .map(|m| {
+                let uptime = self.game.state.module_uptime(m.id());
+                let colour = if uptime >= 90 {
+                    self.palette.green()
+                } else if uptime >= 50 {
+                    self.palette.yellow()
+                } else {
+                    self.palette.red()
+                };
+                Spans::from(vec![
+                    Span::raw(format!("{:<16}", m.name())),
+                    Span::styled(
+                        format!("up {:>4}", print_percentage(uptime)),
+                        Style::default().fg(to_color(colour)),
+                    ),
+                    Span::raw(format!(
+                        "  condition {}",
+                        print_percentage(outpost.module_condition(m.id()))
+                    )),
+                ])
+            })
+            .collect();
+        let title = format!("Module Uptime (last {} turns)", HISTORY_TURNS);
+        f.render_widget(
+            Paragraph::new(lines).block(self.border(&title, false)),
+            area,
+        )
+    }
+
+    fn overview_status<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let outpost = &self.game.outpost;
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = vec![
+            Spans::from(format!(
+                "shielding: {} fewer crises",
+                print_percentage(outpost.shielding().clamp(0, MAX_PROTECTION))
+            )),
+            Spans::from(format!(
+                "defense: {} less damage from raiders",
+                print_percentage(outpost.defense().clamp(0, MAX_PROTECTION))
+            )),
+            Spans::from(""),
+            Spans::from(Span::styled("objectives", bold)),
+        ];
+        for objective in &self.game.objectives {
+            let marker = if self.game.is_met(objective) {
+                "x"
+            } else {
+                " "
+            };
+            lines.push(Spans::from(format!(
+                "[{}] {}",
+                marker,
+                objective.description()
+            )));
+        }
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(Span::styled("missions", bold)));
+        match self.game.sector.get_active_mission() {
+            Some(active_mission) => {
+                let mission = self.game.sector.get_mission(&active_mission.mission_id);
+                lines.push(Spans::from(format!(
+                    "{} with {} crew: {}",
+                    mission.mission_type,
+                    active_mission.crew_len(),
+                    active_mission.state.to_string()
+                )));
+            }
+            None => lines.push(Spans::from("no mission active")),
+        }
+        f.render_widget(
+            Paragraph::new(lines).block(self.border("Status", false)),
+            area,
+        )
+    }
+
+    fn overview_alerts<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let outpost = &self.game.outpost;
+        let warning = Style::default().fg(to_color(self.palette.yellow()));
+        let danger = Style::default().fg(to_color(self.palette.red()));
+        let mut lines = vec![];
+
+        for m in outpost.modules() {
+            let condition = outpost.module_condition(m.id());
+            if condition <= 0 {
+                lines.push(Spans::from(Span::styled(
+                    format!("the {} is broken", m.name()),
+                    danger,
+                )));
+            } else if condition < 50 {
+                lines.push(Spans::from(Span::styled(
+                    format!("the {} needs repairs", m.name()),
+                    warning,
+                )));
+            }
+        }
+        if let Some(record) = self.game.state.history.last() {
+            if record.crew.hungry > 0 {
+                lines.push(Spans::from(Span::styled(
+                    format!("{} crew went hungry", record.crew.hungry),
+                    danger,
+                )));
+            }
+            if record.crew.thirsty > 0 {
+                lines.push(Spans::from(Span::styled(
+                    format!("{} crew went thirsty", record.crew.thirsty),
+                    danger,
+                )));
+            }
+        }
+        if let Some(active_mission) = self.game.sector.get_active_mission() {
+            if active_mission.pending_event.is_some() {
+                lines.push(Spans::from(Span::styled(
+                    "the mission awaits a decision",
+                    warning,
+                )));
+            }
+        }
+
+        let forecast = &self.forecast;
+        if lines.is_empty() && forecast.is_calm() {
+            lines.push(Spans::from(format!(
+                "nothing to worry about for the next {} turns",
+                forecast.turns
+            )));
+        }
+        if let Some(turn) = forecast.food_runs_out {
+            lines.push(Spans::from(Span::styled(
+                format!("food runs out in {} turns", turn),
+                warning,
+            )));
+        }
+        if let Some(turn) = forecast.water_runs_out {
+            lines.push(Spans::from(Span::styled(
+                format!("water runs out in {} turns", turn),
+                warning,
+            )));
+        }
+        for (name, turn) in &forecast.cut_modules {
+            lines.push(Spans::from(Span::styled(
+                format!("the {} loses energy in {} turns", name, turn),
+                warning,
+            )));
+        }
+        for (name, turn) in &forecast.deaths {
+            lines.push(Spans::from(Span::styled(
+                format!("{} starves in {} turns", name, turn),
+                danger,
+            )));
+        }
+        f.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: true })
+                .block(self.border("Alerts", false)),
+            area,
+        )
+    }
+
     fn mission_summary<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
         let mut focused = false;
         match self.current_state() {
@@ -698,9 +1400,35 @@ impl App {
                     active_mission.state.to_string(),
                     Style::default().fg(to_color(self.palette.text())),
                 )];
+                if active_mission.pending_event.is_some() {
+                    mission_status.push(Span::styled(
+                        " (awaiting decision)",
+                        Style::default().fg(to_color(self.palette.red())),
+                    ));
+                }
+                let mission = self.game.sector.get_mission(&active_mission.mission_id);
+                let risk = active_mission.risk(&mission.mission_type);
+                let risk_colour = if risk < 0.25 {
+                    self.palette.green()
+                } else if risk < 0.5 {
+                    self.palette.yellow()
+                } else {
+                    self.palette.red()
+                };
+                let risk = Span::styled(
+                    format!("risk: {}", print_percentage((risk * 100.0).round() as i32)),
+                    Style::default().fg(to_color(risk_colour)),
+                );
+                let mut supplies = vec![Span::styled(
+                    "supplies: ",
+                    Style::default().fg(to_color(self.palette.text())),
+                )];
+                supplies.extend(self.resource_string(&active_mission.supplies));
                 Paragraph::new(vec![
                     Spans::from(mission_status),
+                    Spans::from(risk),
                     Spans::from(self.resource_string(&active_mission.resources)),
+                    Spans::from(supplies),
                 ])
             }
             None => Paragraph::new(Span::styled(
@@ -717,7 +1445,23 @@ impl App {
     }
 
     fn logs<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
-        f.render_widget(self.border(&String::from("Logs"), false), area)
+        let logs: Vec<ListItem> = self
+            .game
+            .state
+            .logs
+            .iter()
+            .rev()
+            .map(|l| {
+                ListItem::new(Spans::from(vec![Span::styled(
+                    l,
+                    Style::default().fg(to_color(self.palette.text())),
+                )]))
+            })
+            .collect();
+        f.render_widget(
+            List::new(logs).block(self.border(&String::from("Logs"), false)),
+            area,
+        )
     }
 
     fn research_summary<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
@@ -730,38 +1474,147 @@ impl App {
     }
 
     fn resource_string(&self, resource: &Resources) -> Vec<Span> {
-        let mut result = vec![];
-        if resource.energy != 0 {
-            result.push(Span::styled(
-                format!("{}e ", print_i32(resource.energy)),
-                Style::default().fg(to_color(self.palette.yellow())),
-            ))
+        resource
+            .iter()
+            .filter(|(_, v)| *v != 0)
+            .map(|(t, v)| {
+                let metadata = t.metadata();
+                Span::styled(
+                    format!("{}{} ", print_i32(v), metadata.glyph),
+                    Style::default().fg(to_color((metadata.colour)(self.palette))),
+                )
+            })
+            .collect()
+    }
+
+    /// a description with the options to respond to it
+    fn decision<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        title: &str,
+        description: &str,
+        options: Vec<&str>,
+        selected: usize,
+    ) {
+        use Constraint::*;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Length(4), Min(0)].as_ref())
+            .split(area);
+
+        f.render_widget(
+            Paragraph::new(Spans::from(description))
+                .block(self.border(title, false))
+                .wrap(Wrap { trim: true }),
+            chunks[0],
+        );
+
+        let options: Vec<ListItem> = options
+            .into_iter()
+            .map(|o| {
+                ListItem::new(Spans::from(vec![Span::styled(
+                    o,
+                    Style::default().fg(to_color(self.palette.text())),
+                )]))
+            })
+            .collect();
+
+        let mut state: ListState = ListState::default();
+        state.select(Some(selected));
+
+        f.render_stateful_widget(
+            List::new(options)
+                .block(self.border("Options", true))
+                .highlight_style(
+                    Style::default()
+                        .add_modifier(Modifier::BOLD)
+                        .bg(to_color(self.palette.overlay0())),
+                )
+                .highlight_symbol("> "),
+            chunks[1],
+            &mut state,
+        );
+    }
+
+    fn mission_report_lines(&self, report: &MissionReport) -> Vec<Spans> {
+        let subtext = Style::default().fg(to_color(self.palette.subtext0()));
+        let mut lines = vec![Spans::from(format!("turns spent: {}", report.turns))];
+
+        let mut total = vec![Span::raw("gathered: ")];
+        total.append(&mut self.resource_string(&report.total_gathered()));
+        lines.push(Spans::from(total));
+        if report.blueprints > 0 {
+            lines.push(Spans::from(format!("blueprints: {}", report.blueprints)));
         }
-        if resource.living_space != 0 {
-            result.push(Span::styled(
-                format!("{}l ", print_i32(resource.living_space)),
-                Style::default().fg(to_color(self.palette.peach())),
-            ))
+        for (day, gathered) in report.gathered.iter().enumerate() {
+            let mut spans = vec![Span::styled(format!("  day {}: ", day + 1), subtext)];
+            spans.append(&mut self.resource_string(gathered));
+            lines.push(Spans::from(spans));
         }
-        if resource.minerals != 0 {
-            result.push(Span::styled(
-                format!("{}m ", print_i32(resource.minerals)),
-                Style::default().fg(to_color(self.palette.sapphire())),
-            ))
+
+        lines.push(Spans::from("events:"));
+        if report.events.is_empty() {
+            lines.push(Spans::from(Span::styled("  none", subtext)));
         }
-        if resource.food != 0 {
-            result.push(Span::styled(
-                format!("{}f ", print_i32(resource.food)),
-                Style::default().fg(to_color(self.palette.green())),
-            ))
+        for event in &report.events {
+            lines.push(Spans::from(Span::styled(format!("  {}", event), subtext)));
         }
-        if resource.water != 0 {
-            result.push(Span::styled(
-                format!("{}w ", print_i32(resource.water)),
-                Style::default().fg(to_color(self.palette.blue())),
-            ))
+
+        lines.push(Spans::from("crew:"));
+        for change in &report.crew {
+            let health = match change.before {
+                Some(before) => format!("  {}: health {} -> {}", change.name, before, change.after),
+                None => format!("  {}: joined, health {}", change.name, change.after),
+            };
+            lines.push(Spans::from(Span::styled(health, subtext)));
         }
-        result
+        for name in &report.casualties {
+            lines.push(Spans::from(Span::styled(
+                format!("  {}: died", name),
+                Style::default().fg(to_color(self.palette.red())),
+            )));
+        }
+        lines
+    }
+
+    fn mission_deadline(&self, mission: &Mission) -> Span {
+        match mission.turns_left(self.game.state.current_turn) {
+            Some(turns) => {
+                let colour = if turns <= 2 {
+                    self.palette.red()
+                } else {
+                    self.palette.subtext0()
+                };
+                Span::styled(
+                    format!("({} turns left)", turns),
+                    Style::default().fg(to_color(colour)),
+                )
+            }
+            None => Span::styled(
+                "(no deadline)",
+                Style::default().fg(to_color(self.palette.subtext0())),
+            ),
+        }
+    }
+
+    fn sub_sector_item(&self, coordinates: &Coordinates, sector: &SubSector) -> ListItem {
+        use SectorType::*;
+        let sector_name = match sector.sector_type {
+            EmptySpace => "empty space",
+            SolarSystem => "solar system",
+            GasCloud => "gas cloud",
+            StellarRift => "stellar rift",
+        };
+        let (sector_name, colour) = match sector.visibility {
+            Visibility::Undiscovered => ("unknown", self.palette.overlay0()),
+            Visibility::Discovered => (sector_name, self.palette.subtext0()),
+            Visibility::Visited => (sector_name, self.palette.text()),
+        };
+        ListItem::new(Spans::from(vec![Span::styled(
+            format!("({}, {}) {}", coordinates.x, coordinates.y, sector_name),
+            Style::default().fg(to_color(colour)),
+        )]))
     }
 
     fn focus<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
@@ -769,9 +1622,7 @@ impl App {
         use Direction::*;
         use State::*;
         match self.current_state() {
-            Overview => {
-                f.render_widget(self.border(&self.current_state().to_string(), false), area)
-            }
+            Overview => self.overview(f, area),
             Crew(i) => {
                 if self.game.outpost.crew_len() <= *i {
                     return;
@@ -859,6 +1710,9 @@ impl App {
                 let mut resource_flow = vec![Span::raw("resource flow: ")];
                 resource_flow.append(&mut self.resource_string(&flow));
 
+                let mut storage = vec![Span::raw("storage: ")];
+                storage.append(&mut self.resource_string(&description.storage));
+
                 let slots = description
                     .energy_levels
                     .iter()
@@ -874,6 +1728,20 @@ impl App {
                     slots,
                     description.energy_levels.len()
                 ));
+                let priority = Span::raw(format!("priority: {}", description.priority));
+                let mut condition = vec![Span::styled(
+                    format!(
+                        "condition: {}% (output {}%), repair: ",
+                        description.condition, description.efficiency
+                    ),
+                    Style::default().fg(to_color(match description.efficiency {
+                        100 => self.palette.text(),
+                        0 => self.palette.red(),
+                        _ => self.palette.yellow(),
+                    })),
+                )];
+                condition
+                    .append(&mut self.resource_string(&self.game.outpost.repair_cost(&module_id)));
                 let assigned_slots = Span::styled(
                     format!("assigned slots: {}/{}", assigned_slots, slots),
                     Style::default().fg(to_color(if assigned_slots > 0 {
@@ -885,7 +1753,7 @@ impl App {
 
                 let chunks = Layout::default()
                     .direction(Vertical)
-                    .constraints([Length(5), Min(0)].as_ref())
+                    .constraints([Length(8), Min(0)].as_ref())
                     .split(area);
 
                 let energy_level_chunks = Layout::default()
@@ -896,8 +1764,11 @@ impl App {
                 f.render_widget(
                     Paragraph::new(vec![
                         Spans::from(resource_flow),
+                        Spans::from(storage),
                         Spans::from(energy_level),
+                        Spans::from(priority),
                         Spans::from(assigned_slots),
+                        Spans::from(condition),
                     ])
                     .block(self.border(description.name, false)),
                     chunks[0],
@@ -937,7 +1808,20 @@ impl App {
                     vec!["down (e.g. in lists)", "j"],
                     vec!["increment energy", "+"],
                     vec!["decrement energy", "-"],
+                    vec!["raise module priority", ">"],
+                    vec!["lower module priority", "<"],
+                    vec!["repair module with minerals (in modules)", "f"],
                     vec!["assign to module", "a"],
+                    vec!["build ship (in modules)", "b"],
+                    vec!["select ship (in mission)", "s"],
+                    vec!["open mission event (in sector)", "e"],
+                    vec!["mission archive (in sector)", "a"],
+                    vec!["remove crew member (in mission)", "d"],
+                    vec!["move crew member up/down (in mission)", "K/J"],
+                    vec!["new game (in game menu)", "n"],
+                    vec!["start the next run (at end of run)", "Enter"],
+                    vec!["change option (in new game)", "h/l"],
+                    vec!["enter a shared seed (in new game)", "p"],
                 ];
 
                 let header_cells = header_data.iter().map(|h| {
@@ -947,14 +1831,109 @@ impl App {
                 let rows = data
                     .iter()
                     .map(|row| Row::new(row.iter().map(|c| Cell::from(*c))));
+                let chunks = Layout::default()
+                    .direction(Vertical)
+                    .constraints([Length(Unlock::ALL.len() as u16 + 6), Min(0)].as_ref())
+                    .split(area);
+
+                let mut profile = vec![Spans::from(format!(
+                    "seed of this run: {}",
+                    self.game.state.seed
+                ))];
+                profile.push(Spans::from(format!(
+                    "runs: {}, wins: {}, most turns survived: {}",
+                    self.profile.runs.len(),
+                    self.profile.wins(),
+                    self.profile.best_turns()
+                )));
+                profile.push(Spans::from("unlocks:"));
+                for unlock in Unlock::ALL {
+                    profile.push(if self.profile.has(unlock) {
+                        Spans::from(format!("  {}: {}", unlock.name(), unlock.description()))
+                    } else {
+                        Spans::from(Span::styled(
+                            format!("  {}: locked, {}", unlock.name(), unlock.requirement()),
+                            Style::default().fg(to_color(self.palette.subtext0())),
+                        ))
+                    });
+                }
+                f.render_widget(
+                    Paragraph::new(profile).block(self.border("Profile", false)),
+                    chunks[0],
+                );
+
                 f.render_widget(
                     Table::new(rows)
                         .header(header)
                         .block(self.border(&String::from("Game Menu"), false))
                         .widths(&[Constraint::Percentage(70), Constraint::Percentage(30)]),
-                    area,
+                    chunks[1],
                 )
             }
+            BuildShip(c) => {
+                let outpost = &self.game.outpost;
+                let chunks = Layout::default()
+                    .direction(Vertical)
+                    .constraints([Percentage(50), Percentage(50)].as_ref())
+                    .split(area);
+
+                let classes: Vec<ListItem> = ShipClass::ALL
+                    .iter()
+                    .map(|class| {
+                        let metadata = class.metadata();
+                        let mut spans = vec![Span::styled(
+                            format!(
+                                "{} (crew {}, cargo {}m, speed {}, fuel {}m/turn) ",
+                                metadata.name,
+                                metadata.crew_capacity,
+                                metadata.cargo_capacity,
+                                metadata.speed,
+                                metadata.fuel_use
+                            ),
+                            Style::default().fg(to_color(self.palette.text())),
+                        )];
+                        spans.append(&mut self.resource_string(&metadata.build_cost));
+                        ListItem::new(Spans::from(spans))
+                    })
+                    .collect();
+
+                let mut state: ListState = ListState::default();
+                state.select(Some(*c));
+                f.render_stateful_widget(
+                    List::new(classes)
+                        .block(self.border("Build Ship", true))
+                        .highlight_style(
+                            Style::default()
+                                .add_modifier(Modifier::BOLD)
+                                .bg(to_color(self.palette.overlay0())),
+                        )
+                        .highlight_symbol("> "),
+                    chunks[0],
+                    &mut state,
+                );
+
+                let ships: Vec<ListItem> = outpost
+                    .ships()
+                    .iter()
+                    .map(|ship| {
+                        ListItem::new(Span::styled(
+                            ship.name().clone(),
+                            Style::default().fg(to_color(self.palette.text())),
+                        ))
+                    })
+                    .collect();
+                f.render_widget(
+                    List::new(ships).block(self.border(
+                        &format!(
+                            "Docked Ships ({}/{} bays)",
+                            outpost.ships().len(),
+                            outpost.docking_bays()
+                        ),
+                        false,
+                    )),
+                    chunks[1],
+                );
+            }
             AssignToModule(c, _) => {
                 let crew_id = self.game.outpost.crew_member_id_by_index(*c);
                 let crew_member: &CrewMember = self.game.outpost.get_crew_member(&crew_id);
@@ -966,30 +1945,36 @@ impl App {
                 self.crew_list_assign_to_module(f, module, area);
             }
             Research => {
-                f.render_widget(self.border(&self.current_state().to_string(), false), area)
+                let target = self.game.objectives.iter().find_map(|o| match o {
+                    Objective::FinalResearch(blueprints) => Some(*blueprints),
+                    _ => None,
+                });
+                let blueprints = match target {
+                    Some(target) => format!(
+                        "blueprints: {}/{} for the jump drive",
+                        self.game.outpost.blueprints(),
+                        target
+                    ),
+                    None => format!("blueprints: {}", self.game.outpost.blueprints()),
+                };
+                f.render_widget(
+                    Paragraph::new(Spans::from(blueprints))
+                        .block(self.border(&self.current_state().to_string(), false)),
+                    area,
+                )
             }
             Sector(x, y) => {
-                use SectorType::*;
                 let mut state: ListState = ListState::default();
                 let mut regions: Vec<ListItem> = vec![];
                 for (coordinates, sector) in self.game.sector.sub_sectors_map() {
                     if coordinates.x == *x && coordinates.y == *y {
                         state.select(Some(regions.len()));
                     }
-                    let sector_name = match sector.sector_type {
-                        EmptySpace => "empty space",
-                        SolarSystem => "solar system",
-                        GasCloud => "gas cloud",
-                        StellarRift => "stellar rift",
-                    };
-                    regions.push(ListItem::new(Spans::from(vec![Span::styled(
-                        format!("({}, {}) {}", coordinates.x, coordinates.y, sector_name),
-                        Style::default().fg(to_color(self.palette.text())),
-                    )])))
+                    regions.push(self.sub_sector_item(coordinates, sector));
                 }
                 let chunks = Layout::default()
                     .direction(Vertical)
-                    .constraints(vec![Percentage(80), Percentage(20)])
+                    .constraints(vec![Percentage(60), Percentage(40)])
                     .split(area);
 
                 f.render_stateful_widget(
@@ -1008,39 +1993,40 @@ impl App {
                 let missions: Vec<ListItem> = self
                     .game
                     .sector
-                    .missions_at(*x, *y)
+                    .mission_board()
                     .iter()
-                    .map(|_m| {
-                        ListItem::new(Spans::from(vec![Span::styled(
-                            "Mining Mission",
-                            Style::default().fg(to_color(self.palette.text())),
-                        )]))
+                    .map(|(coordinates, m)| {
+                        let colour = if coordinates.x == *x && coordinates.y == *y {
+                            self.palette.text()
+                        } else {
+                            self.palette.subtext0()
+                        };
+                        ListItem::new(Spans::from(vec![
+                            Span::styled(
+                                format!(
+                                    "({}, {}) {} ",
+                                    coordinates.x, coordinates.y, m.mission_type
+                                ),
+                                Style::default().fg(to_color(colour)),
+                            ),
+                            self.mission_deadline(m),
+                        ]))
                     })
                     .collect();
 
                 f.render_widget(
-                    List::new(missions).block(self.border(&String::from("Missions"), false)),
+                    List::new(missions).block(self.border(&String::from("Mission Board"), false)),
                     chunks[1],
                 );
             }
             SelectMission(x, y, m) => {
-                use SectorType::*;
                 let mut map_state: ListState = ListState::default();
                 let mut regions: Vec<ListItem> = vec![];
-                for (coordinates, sector) in &self.game.sector.sub_sectors_map() {
+                for (coordinates, sector) in self.game.sector.sub_sectors_map() {
                     if coordinates.x == *x && coordinates.y == *y {
                         map_state.select(Some(regions.len()));
                     }
-                    let sector_name = match sector.sector_type {
-                        EmptySpace => "empty space",
-                        SolarSystem => "solar system",
-                        GasCloud => "gas cloud",
-                        StellarRift => "stellar rift",
-                    };
-                    regions.push(ListItem::new(Spans::from(vec![Span::styled(
-                        format!("({}, {}) {}", coordinates.x, coordinates.y, sector_name),
-                        Style::default().fg(to_color(self.palette.text())),
-                    )])))
+                    regions.push(self.sub_sector_item(coordinates, sector));
                 }
                 let chunks = Layout::default()
                     .direction(Vertical)
@@ -1061,11 +2047,14 @@ impl App {
                     .sector
                     .missions_at(*x, *y)
                     .iter()
-                    .map(|_m| {
-                        ListItem::new(Spans::from(vec![Span::styled(
-                            "Mining Mission",
-                            Style::default().fg(to_color(self.palette.text())),
-                        )]))
+                    .map(|m| {
+                        ListItem::new(Spans::from(vec![
+                            Span::styled(
+                                format!("{} ", m.mission_type),
+                                Style::default().fg(to_color(self.palette.text())),
+                            ),
+                            self.mission_deadline(m),
+                        ]))
                     })
                     .collect();
 
@@ -1085,29 +2074,308 @@ impl App {
                     &mut mission_state,
                 );
             }
-            MissionPreparation(x, y, m) => {
-                let mission_id = self.game.sector.missions_at(*x, *y)[*m].id();
-                let mission = self.game.sector.get_mission(mission_id);
-                let preparation = self.game.outpost.describe_mission_preparation(&mission);
+            MissionPreparation(x, y, m, c) => {
+                let preparation = match self.game.describe_mission_preparation(*x, *y, *m) {
+                    Some(preparation) => preparation,
+                    None => {
+                        f.render_widget(
+                            Paragraph::new(MissionPreparationIssue::UnknownMission.to_string())
+                                .block(self.border("Mission", false)),
+                            area,
+                        );
+                        return;
+                    }
+                };
 
                 let mission_description = Span::raw(match preparation.mission.mission_type {
                     MissionType::Mining(min, max) => format!("mission: Mining {} {}", min, max),
+                    MissionType::Exploration => String::from("mission: Exploration"),
+                    MissionType::Salvage(wrecks) => {
+                        format!("mission: Salvage ({} turns to strip the wreck)", wrecks)
+                    }
+                    MissionType::Rescue => String::from("mission: Rescue"),
                 });
+                let rewards = format!("rewards: {}", preparation.mission.mission_type.rewards());
                 let turn_description = format!("turns: Turns {}", preparation.turns);
+                let ship_description = match preparation.ship {
+                    Some(ship) => format!(
+                        "ship: {} (crew {}, cargo {}m, {} turns of travel each way)",
+                        ship.name(),
+                        ship.crew_capacity(),
+                        ship.cargo_capacity(),
+                        preparation.travel_turns
+                    ),
+                    None => String::from("ship: none"),
+                };
+
+                let mut expected_yield = vec![Span::raw("expected yield per turn: ")];
+                expected_yield
+                    .append(&mut self.resource_string(&preparation.forecast.expected_yield));
+                let success_chance = Span::raw(format!(
+                    "success chance: {}",
+                    print_percentage((preparation.forecast.success_chance * 100.0).round() as i32)
+                ));
+                let combat_chance = Span::raw(format!(
+                    "chance to fight off raiders: {}",
+                    print_percentage((preparation.forecast.combat_chance * 100.0).round() as i32)
+                ));
+                let mut cost = vec![Span::raw("cost: ")];
+                cost.append(&mut self.resource_string(&preparation.cost));
+                let mut shortfall = vec![];
+                if !preparation.shortfall.is_zero() {
+                    shortfall.push(Span::styled(
+                        "missing: ",
+                        Style::default().fg(to_color(self.palette.red())),
+                    ));
+                    shortfall.append(&mut self.resource_string(&preparation.shortfall));
+                }
+
+                let mut lines = vec![
+                    Spans::from(mission_description),
+                    Spans::from(rewards),
+                    Spans::from(turn_description),
+                    Spans::from(ship_description),
+                    Spans::from(expected_yield),
+                    Spans::from(success_chance),
+                    Spans::from(combat_chance),
+                    Spans::from(cost),
+                    Spans::from(shortfall),
+                ];
+                for issue in &preparation.issues {
+                    lines.push(Spans::from(Span::styled(
+                        issue.to_string(),
+                        Style::default().fg(to_color(self.palette.red())),
+                    )));
+                }
+
+                let chunks = Layout::default()
+                    .direction(Vertical)
+                    .constraints([Length(lines.len() as u16 + 2), Min(0)].as_ref())
+                    .split(area);
 
                 f.render_widget(
-                    Paragraph::new(vec![
-                        Spans::from(mission_description),
-                        Spans::from(turn_description),
-                        Spans::from(self.resource_string(&preparation.cost)),
-                    ])
-                    .block(self.border("Mission", false)),
-                    area,
+                    Paragraph::new(lines).block(self.border("Mission", false)),
+                    chunks[0],
+                );
+
+                let crew: Vec<ListItem> = preparation
+                    .crew
+                    .iter()
+                    .map(|c| {
+                        let mut spans = vec![Span::styled(
+                            format!("{} ", c.name),
+                            Style::default().fg(to_color(self.palette.text())),
+                        )];
+                        spans.append(&mut self.resource_string(&c.upkeep.stocks()));
+                        ListItem::new(Spans::from(spans))
+                    })
+                    .collect();
+
+                let mut crew_state: ListState = ListState::default();
+                if !preparation.crew.is_empty() {
+                    crew_state.select(Some(*c));
+                }
+
+                f.render_stateful_widget(
+                    List::new(crew)
+                        .block(self.border("Mission Crew", true))
+                        .highlight_style(
+                            Style::default()
+                                .add_modifier(Modifier::BOLD)
+                                .bg(to_color(self.palette.overlay0())),
+                        )
+                        .highlight_symbol("> "),
+                    chunks[1],
+                    &mut crew_state,
                 );
             }
             AssignCrewToMission(_) => {
                 self.crew_list_assign_to_mission(f, area);
             }
+            MissionArchive(r) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Percentage(40), Percentage(60)].as_ref())
+                    .split(area);
+
+                // the latest report comes first
+                let reports: Vec<&MissionReport> = self.game.archive.iter().rev().collect();
+                let items: Vec<ListItem> = reports
+                    .iter()
+                    .map(|report| {
+                        ListItem::new(Span::styled(
+                            format!(
+                                "turn {}: {} ({}, {})",
+                                report.returned_at, report.title, report.x, report.y
+                            ),
+                            Style::default().fg(to_color(self.palette.text())),
+                        ))
+                    })
+                    .collect();
+
+                let mut state: ListState = ListState::default();
+                if !reports.is_empty() {
+                    state.select(Some(*r));
+                }
+                f.render_stateful_widget(
+                    List::new(items)
+                        .block(self.border("Mission Archive", true))
+                        .highlight_style(
+                            Style::default()
+                                .add_modifier(Modifier::BOLD)
+                                .bg(to_color(self.palette.overlay0())),
+                        )
+                        .highlight_symbol("> "),
+                    chunks[0],
+                    &mut state,
+                );
+
+                let lines = match reports.get(*r) {
+                    Some(report) => self.mission_report_lines(report),
+                    None => vec![Spans::from("No mission returned yet")],
+                };
+                f.render_widget(
+                    Paragraph::new(lines)
+                        .block(self.border("Mission Report", false))
+                        .wrap(Wrap { trim: true }),
+                    chunks[1],
+                );
+            }
+            MissionEvent(o) => {
+                if let Some(event) = self.game.sector.pending_mission_event() {
+                    let options = event.options().iter().map(|o| o.description).collect();
+                    self.decision(f, area, "Mission Event", event.description(), options, *o);
+                }
+            }
+            NewGame(i) => {
+                let difficulty = self.setup.difficulty.config();
+                let options = vec![
+                    format!("seed: {}", GameState::encode_seed(self.setup.seed)),
+                    format!(
+                        "difficulty: {} ({})",
+                        difficulty.name, difficulty.description
+                    ),
+                    format!(
+                        "scenario: {} ({})",
+                        self.setup.scenario.name, self.setup.scenario.description
+                    ),
+                ];
+                let items: Vec<ListItem> = options
+                    .into_iter()
+                    .map(|o| ListItem::new(Spans::from(o)))
+                    .collect();
+                let mut state = ListState::default();
+                state.select(Some(*i));
+                let area = if self.scenario_issues.is_empty() {
+                    area
+                } else {
+                    let chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints(
+                            [
+                                Constraint::Min(0),
+                                Constraint::Length(self.scenario_issues.len() as u16 + 2),
+                            ]
+                            .as_ref(),
+                        )
+                        .split(area);
+                    let lines: Vec<Spans> = self
+                        .scenario_issues
+                        .iter()
+                        .map(|(path, issue)| {
+                            Spans::from(Span::styled(
+                                format!("skipped {}: {}", path, issue),
+                                Style::default().fg(to_color(self.palette.red())),
+                            ))
+                        })
+                        .collect();
+                    f.render_widget(
+                        Paragraph::new(lines)
+                            .block(self.border("Scenario Issues", false))
+                            .wrap(Wrap { trim: true }),
+                        chunks[1],
+                    );
+                    chunks[0]
+                };
+                f.render_stateful_widget(
+                    List::new(items)
+                        .block(self.border(&self.current_state().to_string(), true))
+                        .highlight_style(
+                            Style::default()
+                                .add_modifier(Modifier::BOLD)
+                                .bg(to_color(self.palette.overlay0())),
+                        )
+                        .highlight_symbol("> "),
+                    area,
+                    &mut state,
+                )
+            }
+            EnterSeed => {
+                let mut lines = vec![
+                    Spans::from("paste or type a shared seed and press Enter"),
+                    Spans::from(""),
+                    Spans::from(Span::styled(
+                        format!("{}_", self.seed_input),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )),
+                ];
+                if let Some(issue) = &self.seed_issue {
+                    lines.push(Spans::from(""));
+                    lines.push(Spans::from(Span::styled(
+                        issue.to_string(),
+                        Style::default().fg(to_color(self.palette.red())),
+                    )));
+                }
+                f.render_widget(
+                    Paragraph::new(lines)
+                        .block(self.border(&self.current_state().to_string(), true)),
+                    area,
+                )
+            }
+            RunSummary => {
+                if let Some(summary) = self.game.summary() {
+                    let (headline, colour) = if summary.outcome.is_victory() {
+                        ("Victory", self.palette.green())
+                    } else {
+                        ("Defeat", self.palette.red())
+                    };
+                    let lines = vec![
+                        Spans::from(Span::styled(
+                            headline,
+                            Style::default()
+                                .fg(to_color(colour))
+                                .add_modifier(Modifier::BOLD),
+                        )),
+                        Spans::from(summary.outcome.description()),
+                        Spans::from(""),
+                        Spans::from(format!("turns survived: {}", summary.turns)),
+                        Spans::from(format!("crew left: {}", summary.crew)),
+                        Spans::from(format!("crew lost: {}", summary.casualties)),
+                        Spans::from(format!("missions completed: {}", summary.missions)),
+                        Spans::from(format!("blueprints: {}", summary.blueprints)),
+                        Spans::from(format!(
+                            "sub sectors visited: {}/{}",
+                            summary.visited_sub_sectors, summary.sub_sectors
+                        )),
+                        Spans::from(""),
+                        Spans::from(Span::styled(
+                            "press Enter to start the next run",
+                            Style::default().fg(to_color(self.palette.subtext0())),
+                        )),
+                    ];
+                    f.render_widget(
+                        Paragraph::new(lines)
+                            .block(self.border(&self.current_state().to_string(), true)),
+                        area,
+                    )
+                }
+            }
+            Threat(o) => {
+                if let Some(threat) = self.game.outpost.pending_threat() {
+                    let options = threat.options().iter().map(|o| o.description).collect();
+                    self.decision(f, area, "Crisis", threat.description(), options, *o);
+                }
+            }
         }
     }
 }
@@ -1147,7 +2415,7 @@ mod tests {
         let assert_index = |expected: usize, index: i32, arr: &Vec<i32>| {
             assert_eq!(
                 expected,
-                circular_index(index, arr),
+                circular_index(index, arr.len()),
                 "indexing {} should create circular index {}",
                 index,
                 expected
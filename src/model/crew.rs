@@ -1,6 +1,8 @@
-use super::{modules::Module, resources::Resources, stats::Stats, Entity};
+use super::{game_state::GameState, modules::Module, resources::Resources, stats::Stats, Entity};
 
 use nanoid::nanoid;
+use rand::seq::SliceRandom;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -14,6 +16,12 @@ pub struct CrewMember {
     health: i32,
     assigned_module: Option<String>,
     assigned_mission: Option<String>,
+    #[serde(default = "default_upkeep")]
+    upkeep: Resources,
+}
+
+fn default_upkeep() -> Resources {
+    Resources::living_space(1) + Resources::food(1) + Resources::water(1)
 }
 
 impl CrewMember {
@@ -28,9 +36,31 @@ impl CrewMember {
             health: 5,
             assigned_module: None,
             assigned_mission: None,
+            upkeep: default_upkeep(),
         }
     }
 
+    /// a stranger with a random name who is trained in one field
+    pub fn random(state: &mut GameState) -> Self {
+        const NAMES: [&str; 8] = [
+            "Ada", "Bram", "Cleo", "Dov", "Esme", "Falk", "Greta", "Hiro",
+        ];
+        let name = NAMES.choose(&mut state.rng).unwrap().to_string();
+        let skill = state.rng.gen_range(10..50);
+        let stats = match state.rng.gen_range(0..6) {
+            0 => Stats::biology(skill),
+            1 => Stats::chemistry(skill),
+            2 => Stats::engineering(skill),
+            3 => Stats::geology(skill),
+            4 => Stats::astrophysics(skill),
+            _ => Stats::military(skill),
+        };
+        let mut crew_member = CrewMember::new(name);
+        crew_member.stats = stats;
+        crew_member.set_upkeep(state.config().crew_upkeep);
+        crew_member
+    }
+
     pub fn name(&self) -> &String {
         &self.name
     }
@@ -40,17 +70,24 @@ impl CrewMember {
     pub fn health(&self) -> i32 {
         (self.health * 20) as i32
     }
+    pub fn is_hungry(&self) -> bool {
+        self.is_hungry
+    }
+    pub fn is_thirsty(&self) -> bool {
+        self.is_thirsty
+    }
+    pub fn is_tired(&self) -> bool {
+        self.is_tired
+    }
     pub fn is_alive(&self) -> bool {
         self.health > 0
     }
     pub fn upkeep(&self) -> Resources {
-        Resources {
-            energy: 0,
-            living_space: 1,
-            minerals: 0,
-            food: 1,
-            water: 1,
-        }
+        self.upkeep.clone()
+    }
+    /// the upkeep depends on the difficulty and is set when joining the outpost
+    pub fn set_upkeep(&mut self, upkeep: Resources) {
+        self.upkeep = upkeep
     }
     pub fn mood(&self) -> i32 {
         let mut m: i32 = 70;
@@ -69,6 +106,19 @@ impl CrewMember {
         let mood_modifier = self.mood() as f32 / 50.0;
         (stat_bonus * (1.0 + mood_modifier)).ceil() as i32
     }
+    /// the stats as they count on a mission, a tired or injured crew member performs worse
+    pub fn effective_stats(&self) -> Stats {
+        let effective =
+            |stat: i32| std::cmp::max(self.apply_mood(stat as f32), 0) * self.health() / 100;
+        Stats {
+            biology: effective(self.stats.biology),
+            chemistry: effective(self.stats.chemistry),
+            engineering: effective(self.stats.engineering),
+            geology: effective(self.stats.geology),
+            astrophysics: effective(self.stats.astrophysics),
+            military: effective(self.stats.military),
+        }
+    }
 
     pub fn finish_turn(&mut self) {
         if self.is_hungry {
@@ -81,6 +131,9 @@ impl CrewMember {
         self.is_thirsty = true;
         self.is_tired = true;
     }
+    pub fn injure(&mut self, amount: i32) {
+        self.health -= amount;
+    }
     pub fn eat(&mut self) {
         self.is_hungry = false;
     }
@@ -105,6 +158,9 @@ impl CrewMember {
     pub fn assign_to_mission(&mut self, mission_id: &String) {
         self.assigned_mission = Some(mission_id.clone())
     }
+    pub fn unassign_from_mission(&mut self) {
+        self.assigned_mission = None
+    }
     pub fn is_assigned_to_module(&self, module: &Box<dyn Module>) -> bool {
         self.assigned_module
             .as_ref()
@@ -118,3 +174,55 @@ impl Entity for CrewMember {
         &self.id
     }
 }
+
+/// the combined effective stats of a team
+pub fn team_stats<'a>(crew: impl Iterator<Item = &'a CrewMember>) -> Stats {
+    crew.map(|c| c.effective_stats())
+        .fold(Stats::zero(), |a, b| a + b)
+}
+
+pub fn team_upkeep<'a>(crew: impl Iterator<Item = &'a CrewMember>) -> Resources {
+    crew.map(|c| c.upkeep())
+        .fold(Resources::zero(), |a, b| a + b)
+}
+
+/// chance to succeed at a task, a team without any skill still has a slim chance
+pub fn skill_chance(skill: i32) -> f64 {
+    (0.3 + skill as f64 / 200.0).clamp(0.05, 0.95)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{skill_chance, team_stats, CrewMember};
+    use crate::model::stats::Stats;
+
+    #[test]
+    fn effective_stats_respect_mood_and_health() {
+        let mut member = CrewMember::new("a".to_string());
+        member.stats = Stats::geology(50);
+        assert_eq!(90, member.effective_stats().geology);
+
+        member.injure(1);
+        assert_eq!(72, member.effective_stats().geology);
+
+        member.finish_turn();
+        assert_eq!(0, member.effective_stats().geology);
+    }
+
+    #[test]
+    fn team_stats_add_up() {
+        let mut a = CrewMember::new("a".to_string());
+        a.stats = Stats::military(10);
+        let mut b = CrewMember::new("b".to_string());
+        b.stats = Stats::military(20);
+
+        assert_eq!(54, team_stats(vec![&a, &b].into_iter()).military);
+    }
+
+    #[test]
+    fn skill_chance_is_bounded() {
+        assert_eq!(0.3, skill_chance(0));
+        assert_eq!(0.8, skill_chance(100));
+        assert_eq!(0.95, skill_chance(1000));
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use super::resources::Resources;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+/// every number that differs between the difficulty presets
+pub struct DifficultyConfig {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// what each crew member needs per turn
+    pub crew_upkeep: Resources,
+    /// the chance of a crisis each turn before shields
+    pub threat_chance: f64,
+    /// percentage of the regular mission yield
+    pub mission_yield: i32,
+    /// added to the regular starting stock
+    pub starting_bonus: Resources,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn config(&self) -> DifficultyConfig {
+        use Difficulty::*;
+        match self {
+            Easy => DifficultyConfig {
+                name: "easy",
+                description: "rare crises, rich missions and a well stocked outpost",
+                crew_upkeep: Resources::living_space(1) + Resources::food(1) + Resources::water(1),
+                threat_chance: 0.08,
+                mission_yield: 150,
+                starting_bonus: Resources::minerals(10)
+                    + Resources::food(10)
+                    + Resources::water(10),
+            },
+            Normal => DifficultyConfig {
+                name: "normal",
+                description: "the outpost as it was planned",
+                crew_upkeep: Resources::living_space(1) + Resources::food(1) + Resources::water(1),
+                threat_chance: 0.15,
+                mission_yield: 100,
+                starting_bonus: Resources::zero(),
+            },
+            Hard => DifficultyConfig {
+                name: "hard",
+                description: "hungry crew, frequent crises and meagre missions",
+                crew_upkeep: Resources::living_space(1) + Resources::food(2) + Resources::water(1),
+                threat_chance: 0.25,
+                mission_yield: 75,
+                starting_bonus: Resources::zero(),
+            },
+        }
+    }
+}
@@ -2,6 +2,13 @@ use base64::{engine::general_purpose, Engine as _};
 use rand_core::SeedableRng;
 use rand_pcg::Pcg64;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::difficulty::{Difficulty, DifficultyConfig};
+use super::resources::Resources;
+
+/// turns of history kept for the overview charts
+pub const HISTORY_TURNS: usize = 30;
 
 #[derive(Serialize, Deserialize)]
 pub struct GameState {
@@ -9,25 +16,174 @@ pub struct GameState {
     pub logs: Vec<String>,
     pub current_turn: u32,
     pub rng: Pcg64,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    #[serde(default = "default_scenario")]
+    pub scenario: String,
+    /// the last turns, oldest first
+    #[serde(default)]
+    pub history: Vec<TurnRecord>,
+}
+
+/// the outpost at the end of a turn
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TurnRecord {
+    pub turn: u32,
+    pub resources: Resources,
+    pub energy_charge: i32,
+    pub crew: CrewRecord,
+    /// ids of the modules that ran at least one energy level
+    pub active_modules: Vec<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CrewRecord {
+    pub total: usize,
+    pub hungry: usize,
+    pub thirsty: usize,
+    pub tired: usize,
+    /// the average health in percent
+    pub health: i32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SeedIssue {
+    Empty,
+    NotBase64,
+    WrongLength(usize),
+}
+
+impl fmt::Display for SeedIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use SeedIssue::*;
+        match self {
+            Empty => write!(f, "the seed is empty"),
+            NotBase64 => write!(f, "the seed is not valid base64"),
+            WrongLength(len) => write!(f, "the seed decodes to {} bytes instead of 8", len),
+        }
+    }
+}
+
+fn default_scenario() -> String {
+    String::from("standard")
 }
 
 impl GameState {
     pub fn new(seed: u64) -> Self {
-        let data: [u8; 8] = seed.to_be_bytes();
-        let encoded_seed = general_purpose::STANDARD.encode(&data);
         Self {
-            seed: encoded_seed,
+            seed: GameState::encode_seed(seed),
             logs: vec![],
             current_turn: 0,
             rng: SeedableRng::seed_from_u64(seed),
+            difficulty: Difficulty::default(),
+            scenario: default_scenario(),
+            history: vec![],
+        }
+    }
+
+    pub fn encode_seed(seed: u64) -> String {
+        let data: [u8; 8] = seed.to_be_bytes();
+        general_purpose::STANDARD.encode(&data)
+    }
+
+    /// the seed a player pasted, the reverse of `encode_seed`
+    pub fn decode_seed(seed: &str) -> Result<u64, SeedIssue> {
+        let seed = seed.trim();
+        if seed.is_empty() {
+            return Err(SeedIssue::Empty);
         }
+        let data = general_purpose::STANDARD
+            .decode(seed)
+            .map_err(|_| SeedIssue::NotBase64)?;
+        let data: [u8; 8] = data
+            .as_slice()
+            .try_into()
+            .map_err(|_| SeedIssue::WrongLength(data.len()))?;
+        Ok(u64::from_be_bytes(data))
+    }
+
+    pub fn config(&self) -> DifficultyConfig {
+        self.difficulty.config()
     }
 
     pub fn finish_turn(&mut self) {
         self.current_turn += 1;
     }
 
-    fn log<'a>(&mut self, message: &'a str) {
-        self.logs.push(String::from(message))
+    pub fn record(&mut self, record: TurnRecord) {
+        self.history.push(record);
+        if self.history.len() > HISTORY_TURNS {
+            self.history.remove(0);
+        }
+    }
+
+    /// percentage of the recorded turns the module was running
+    pub fn module_uptime(&self, module_id: &String) -> i32 {
+        if self.history.is_empty() {
+            return 0;
+        }
+        let active = self
+            .history
+            .iter()
+            .filter(|r| r.active_modules.contains(module_id))
+            .count();
+        (active * 100 / self.history.len()) as i32
+    }
+
+    pub fn log<'a>(&mut self, message: &'a str) {
+        self.logs
+            .push(format!("turn {}: {}", self.current_turn, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CrewRecord, GameState, SeedIssue, TurnRecord, HISTORY_TURNS};
+    use crate::model::resources::Resources;
+
+    #[test]
+    fn seeds_survive_the_round_trip() {
+        for seed in [0, 1, 42, u64::MAX] {
+            let encoded = GameState::encode_seed(seed);
+            assert_eq!(Ok(seed), GameState::decode_seed(&encoded));
+            assert_eq!(Ok(seed), GameState::decode_seed(&format!(" {}\n", encoded)));
+        }
+    }
+
+    #[test]
+    fn broken_seeds_are_reported() {
+        assert_eq!(Err(SeedIssue::Empty), GameState::decode_seed("  "));
+        assert_eq!(
+            Err(SeedIssue::NotBase64),
+            GameState::decode_seed("not a seed")
+        );
+        assert_eq!(
+            Err(SeedIssue::WrongLength(3)),
+            GameState::decode_seed("AAAA")
+        );
+    }
+
+    #[test]
+    fn history_keeps_the_last_turns() {
+        let mut state = GameState::new(0);
+        let module_id = String::from("power");
+        for turn in 0..HISTORY_TURNS as u32 + 10 {
+            state.record(TurnRecord {
+                turn,
+                resources: Resources::zero(),
+                energy_charge: 0,
+                crew: CrewRecord::default(),
+                active_modules: if turn % 2 == 0 {
+                    vec![module_id.clone()]
+                } else {
+                    vec![]
+                },
+            });
+        }
+
+        assert_eq!(HISTORY_TURNS, state.history.len());
+        assert_eq!(10, state.history[0].turn);
+        assert_eq!(50, state.module_uptime(&module_id));
+        assert_eq!(0, state.module_uptime(&String::from("farm")));
     }
 }
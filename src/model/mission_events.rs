@@ -0,0 +1,190 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::game_state::GameState;
+use super::resources::Resources;
+use super::sector::{MissionType, SectorType};
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MissionEventKind {
+    GasLeak,
+    CaveIn,
+    EquipmentFailure,
+    Anomaly,
+    MineralVein,
+    Micrometeorites,
+    Raiders,
+}
+
+pub enum MissionEventOutcome {
+    Gain(Resources),
+    Loss(Resources),
+    Injury(i32),
+    Abort,
+    /// the crew fights, winning yields the loot, losing injures the crew and costs as much cargo
+    Fight(Resources),
+}
+
+pub struct MissionEventOption {
+    pub description: &'static str,
+    pub outcomes: Vec<MissionEventOutcome>,
+}
+
+impl MissionEventKind {
+    pub const ALL: [MissionEventKind; 7] = [
+        MissionEventKind::GasLeak,
+        MissionEventKind::CaveIn,
+        MissionEventKind::EquipmentFailure,
+        MissionEventKind::Anomaly,
+        MissionEventKind::MineralVein,
+        MissionEventKind::Micrometeorites,
+        MissionEventKind::Raiders,
+    ];
+
+    /// the chance that something happens on site, it grows with every day the crew stays
+    /// and a skilled engineering team keeps the equipment safe
+    pub fn chance(mission_type: &MissionType, days_on_site: u16, engineering: i32) -> f64 {
+        let (base, per_day, max) = match mission_type {
+            MissionType::Mining(_, _) => (0.05, 0.08, 0.9),
+            _ => (0.1, 0.05, 0.6),
+        };
+        let safety = 1.0 - (engineering as f64 / 200.0).clamp(0.0, 0.5);
+        ((base + per_day * days_on_site as f64) * safety).min(max)
+    }
+
+    pub fn draw(
+        state: &mut GameState,
+        sector_type: &SectorType,
+        mission_type: &MissionType,
+        chance: f64,
+    ) -> Option<MissionEventKind> {
+        if !state.rng.gen_bool(chance) {
+            return None;
+        }
+        MissionEventKind::ALL
+            .choose_weighted(&mut state.rng, |k| k.weight(sector_type, mission_type))
+            .ok()
+            .cloned()
+    }
+
+    fn weight(&self, sector_type: &SectorType, mission_type: &MissionType) -> u32 {
+        use MissionEventKind::*;
+        use SectorType::*;
+        let mining = matches!(mission_type, MissionType::Mining(_, _));
+        match (self, sector_type) {
+            (GasLeak, GasCloud) => 4,
+            (GasLeak, _) if mining => 2,
+            (GasLeak, _) => 0,
+            (CaveIn, _) if mining => 3,
+            (CaveIn, _) => 0,
+            (EquipmentFailure, _) if mining => 3,
+            (EquipmentFailure, _) => 1,
+            (Anomaly, StellarRift) => 4,
+            (Anomaly, _) => 1,
+            (MineralVein, SolarSystem) if mining => 3,
+            (MineralVein, _) => 0,
+            (Micrometeorites, EmptySpace) => 3,
+            (Micrometeorites, _) => 1,
+            (Raiders, EmptySpace) => 2,
+            (Raiders, _) => 1,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        use MissionEventKind::*;
+        match self {
+            GasLeak => "A pocket of toxic gas breaches the excavation site.",
+            CaveIn => "A tunnel collapses on the mining crew.",
+            EquipmentFailure => "A drill head shatters and part of the cargo is lost.",
+            Anomaly => "The sensors pick up a strange signal close to the site.",
+            MineralVein => "The crew strikes a rich mineral vein.",
+            Micrometeorites => "A shower of micrometeorites hits the crew.",
+            Raiders => "A raider vessel closes in on the mission crew.",
+        }
+    }
+
+    /// events with a single option resolve immediately, others wait for a decision
+    pub fn options(&self) -> Vec<MissionEventOption> {
+        use MissionEventKind::*;
+        use MissionEventOutcome::*;
+        match self {
+            GasLeak => vec![
+                MissionEventOption {
+                    description: "seal the leak",
+                    outcomes: vec![Injury(1)],
+                },
+                MissionEventOption {
+                    description: "abandon the site",
+                    outcomes: vec![Abort],
+                },
+            ],
+            CaveIn => vec![MissionEventOption {
+                description: "dig out the crew",
+                outcomes: vec![Injury(2)],
+            }],
+            EquipmentFailure => vec![MissionEventOption {
+                description: "continue",
+                outcomes: vec![Loss(Resources::minerals(3))],
+            }],
+            Anomaly => vec![
+                MissionEventOption {
+                    description: "investigate",
+                    outcomes: vec![Gain(Resources::minerals(8)), Injury(1)],
+                },
+                MissionEventOption {
+                    description: "ignore it",
+                    outcomes: vec![],
+                },
+            ],
+            MineralVein => vec![MissionEventOption {
+                description: "continue",
+                outcomes: vec![Gain(Resources::minerals(5))],
+            }],
+            Micrometeorites => vec![MissionEventOption {
+                description: "continue",
+                outcomes: vec![Injury(1)],
+            }],
+            Raiders => vec![
+                MissionEventOption {
+                    description: "fight them off",
+                    outcomes: vec![Fight(Resources::minerals(6))],
+                },
+                MissionEventOption {
+                    description: "flee",
+                    outcomes: vec![Abort],
+                },
+            ],
+        }
+    }
+
+    pub fn requires_decision(&self) -> bool {
+        self.options().len() > 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MissionEventKind;
+    use crate::model::sector::MissionType;
+
+    #[test]
+    fn mining_risk_grows_with_days_on_site() {
+        let mining = MissionType::Mining(5, 10);
+        let first_day = MissionEventKind::chance(&mining, 0, 0);
+        let fifth_day = MissionEventKind::chance(&mining, 5, 0);
+
+        assert!(first_day < fifth_day);
+        assert_eq!(0.9, MissionEventKind::chance(&mining, 100, 0));
+    }
+
+    #[test]
+    fn engineering_reduces_risk() {
+        let mining = MissionType::Mining(5, 10);
+        let untrained = MissionEventKind::chance(&mining, 5, 0);
+        let trained = MissionEventKind::chance(&mining, 5, 100);
+
+        assert_eq!(untrained / 2.0, trained);
+        assert_eq!(trained, MissionEventKind::chance(&mining, 5, 300));
+    }
+}
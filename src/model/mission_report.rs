@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use super::resources::Resources;
+
+#[derive(Serialize, Deserialize)]
+pub struct MissionReport {
+    pub title: String,
+    pub x: i32,
+    pub y: i32,
+    pub returned_at: u32,
+    pub turns: u32,
+    /// the cargo gathered during each turn on site
+    pub gathered: Vec<Resources>,
+    pub blueprints: u32,
+    pub events: Vec<String>,
+    pub crew: Vec<CrewHealthChange>,
+    pub casualties: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CrewHealthChange {
+    pub name: String,
+    /// none for crew members who joined during the mission
+    pub before: Option<i32>,
+    pub after: i32,
+}
+
+impl MissionReport {
+    pub fn total_gathered(&self) -> Resources {
+        self.gathered
+            .iter()
+            .fold(Resources::zero(), |a, b| a + b.clone())
+    }
+}
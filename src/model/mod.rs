@@ -1,17 +1,21 @@
 use self::{
-    game_state::GameState,
-    outpost::Outpost,
-    sector::{Coordinates, MissionType, Sector, SectorType},
-};
-use crate::model::modules::Module;
-use crate::model::{
-    crew::CrewMember,
-    modules::{
-        farm::Farm, living_quarters::LivingQuarters, power_generator::PowerGenerator,
-        water_extractor::WaterExtractor,
+    difficulty::Difficulty,
+    game_state::{GameState, SeedIssue},
+    mission_report::MissionReport,
+    outpost::{
+        MissionPreparationDescription, MissionPreparationIssue, ModuleRepairIssue, Outpost,
+        ShipBuildIssue,
     },
-    sector::SubSector,
+    profile::{Profile, Unlock},
+    resources::Resources,
+    run::{Objective, RunOutcome, RunSummary, COLLAPSE_TURNS},
+    scenario::Scenario,
+    sector::{Coordinates, Sector},
+    ship::{Ship, ShipClass},
+    stats::Stats,
 };
+use crate::model::modules::Module;
+use crate::model::{crew::CrewMember, modules::power_generator::PowerGenerator, sector::SubSector};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::ValuesMut;
@@ -21,83 +25,211 @@ use std::ops::{Index, IndexMut};
 use std::slice::IterMut;
 
 pub mod crew;
+pub mod difficulty;
 pub mod game_state;
+pub mod mission_events;
+pub mod mission_report;
 pub mod modules;
 pub mod outpost;
+pub mod profile;
 pub mod resources;
+pub mod run;
+pub mod scenario;
 pub mod sector;
+pub mod ship;
 pub mod stats;
+pub mod threats;
 
 #[derive(Serialize, Deserialize)]
 pub struct Game {
     pub state: GameState,
     pub outpost: Outpost,
     pub sector: Sector,
+    /// reports of all missions that returned, the latest last
+    #[serde(default)]
+    pub archive: Vec<MissionReport>,
+    /// set once the run is won or lost
+    #[serde(default)]
+    pub outcome: Option<RunOutcome>,
+    /// consecutive turns the outpost has been collapsed
+    #[serde(default)]
+    collapsed_for: u32,
+    /// the ways to win this run as declared by the scenario
+    #[serde(default = "Objective::standard")]
+    pub objectives: Vec<Objective>,
 }
 
-impl Game {
-    pub fn new() -> Self {
-        let mut rng = rand::thread_rng();
-        let state = GameState::new(rng.gen());
-        let mut outpost = Outpost::new();
-
-        let power_generator = Box::new(PowerGenerator::new("power"));
-        outpost.add_module(power_generator);
+/// the choices made before a run starts
+pub struct GameSetup {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub scenario: Scenario,
+}
 
-        let mut quarters = Box::new(LivingQuarters::new("quarters"));
-        quarters.set_energy_level(2);
-        outpost.add_module(quarters);
+impl GameSetup {
+    pub fn random() -> Self {
+        Self {
+            seed: rand::thread_rng().gen(),
+            difficulty: Difficulty::default(),
+            scenario: Scenario::standard(),
+        }
+    }
 
-        let mut water = Box::new(WaterExtractor::new("water"));
-        let water_id = water.id().clone();
-        water.set_energy_level(1);
-        outpost.add_module(water);
+    pub fn reroll_seed(&mut self) {
+        self.seed = rand::thread_rng().gen();
+    }
 
-        let mut farm = Box::new(Farm::new("farm"));
-        let farm_id = farm.id().clone();
-        farm.set_energy_level(1);
-        outpost.add_module(farm);
+    /// replays a run somebody shared, the same seed and scenario lead to the same run
+    pub fn set_seed(&mut self, seed: &str) -> Result<(), SeedIssue> {
+        self.seed = GameState::decode_seed(seed)?;
+        Ok(())
+    }
+}
 
-        let a = CrewMember::new("a".to_string());
-        let a_id = a.id().clone();
-        outpost.add_crew_member(a);
+impl Game {
+    /// a fresh run from the scenario with the starting bonuses the profile has unlocked
+    pub fn new(setup: &GameSetup, profile: &Profile) -> Self {
+        let scenario = &setup.scenario;
+        let mut state = GameState::new(setup.seed);
+        state.difficulty = setup.difficulty;
+        state.scenario = scenario.name.clone();
+        let config = state.config();
 
-        let b = CrewMember::new("b".to_string());
-        let b_id = b.id().clone();
-        outpost.add_crew_member(b);
+        let mut outpost = Outpost::new();
+        outpost.set_resources(scenario.resources.clone() + config.starting_bonus.clone());
+        if profile.has(Unlock::SupplyCache) {
+            outpost.add_resources(
+                Resources::minerals(10) + Resources::food(10) + Resources::water(10),
+            );
+        }
 
-        outpost.add_crew_member(CrewMember::new("c".to_string()));
-        outpost.add_crew_member(CrewMember::new("d".to_string()));
+        for module in &scenario.modules {
+            outpost.add_module(module.build());
+        }
+        if profile.has(Unlock::SpareGenerator) {
+            let mut spare = Box::new(PowerGenerator::new("spare power"));
+            spare.set_energy_level(0);
+            outpost.add_module(spare);
+        }
+        for ship in &scenario.ships {
+            outpost.add_ship(Ship::new(&ship.name, ship.class));
+        }
 
-        outpost.assign_crew_member_to_module(&a_id, &water_id);
-        outpost.assign_crew_member_to_module(&b_id, &farm_id);
+        for (i, c) in scenario.crew.iter().enumerate() {
+            let mut crew_member = CrewMember::new(c.name.clone());
+            crew_member.stats = c.stats.clone();
+            crew_member.set_upkeep(config.crew_upkeep.clone());
+            // the last to join is the veteran
+            if profile.has(Unlock::Veterans) && i + 1 == scenario.crew.len() {
+                crew_member.stats = crew_member.stats + Stats::military(30);
+            }
+            let crew_member_id = crew_member.id().clone();
+            outpost.add_crew_member(crew_member);
+            if let Some(module_name) = &c.module {
+                let module_id = outpost
+                    .modules()
+                    .iter()
+                    .find(|m| m.name() == module_name)
+                    .map(|m| m.id().clone())
+                    .unwrap();
+                outpost.assign_crew_member_to_module(&crew_member_id, &module_id);
+            }
+        }
 
         let mut sector = Sector::new();
-
-        use SectorType::*;
-        sector.add_subsector(-1, -1, SubSector::new(StellarRift));
-        sector.add_subsector(1, -1, SubSector::new(StellarRift));
-        sector.add_subsector(0, 0, SubSector::new(SolarSystem));
-        sector.add_subsector(1, 0, SubSector::new(EmptySpace));
-        sector.add_subsector(-1, 1, SubSector::new(StellarRift));
-        sector.add_subsector(0, 1, SubSector::new(SolarSystem));
-        sector.add_subsector(1, 1, SubSector::new(EmptySpace));
-        sector.add_subsector(0, -1, SubSector::new(SolarSystem));
-
-        use MissionType::*;
-        sector.add_mission(0, -1, Mining(5, 10));
+        for sub_sector in &scenario.sector {
+            sector.add_subsector(
+                sub_sector.x,
+                sub_sector.y,
+                SubSector::new(sub_sector.sector_type.clone()),
+            );
+        }
+        state.record(outpost.turn_record(0));
+        sector.visit(0, 0);
+        for mission in &scenario.missions {
+            sector.add_mission(mission.x, mission.y, mission.mission_type.clone(), None);
+        }
 
         Self {
             state,
             outpost,
             sector,
+            archive: vec![],
+            outcome: None,
+            collapsed_for: 0,
+            objectives: scenario.objectives.clone(),
         }
     }
 
+    /// the turn cannot end while the outpost has not responded to a crisis
     pub fn finish_turn(&mut self) {
+        if self.outcome.is_some() || self.outpost.pending_threat().is_some() {
+            return;
+        }
         self.outpost.finish_turn(&mut self.state);
+        let home = self.sector.sector_type_at(0, 0);
+        self.outpost.roll_threats(&mut self.state, &home);
         self.sector.finish_turn(&mut self.state);
+        if let Some((active_mission, report)) = self.sector.take_returned_mission(&self.state) {
+            self.outpost
+                .receive_mission(&mut self.state, active_mission);
+            self.archive.push(report);
+        }
         self.state.finish_turn();
+        let record = self.outpost.turn_record(self.state.current_turn);
+        self.state.record(record);
+        self.update_outcome();
+    }
+
+    /// the run ends once the crew is lost, the outpost stays collapsed or an objective is met
+    fn update_outcome(&mut self) {
+        use RunOutcome::*;
+        if self.outpost.is_collapsed() {
+            self.collapsed_for += 1;
+        } else {
+            self.collapsed_for = 0;
+        }
+        self.outcome = if self.outpost.crew_len() + self.sector.mission_crew_len() == 0 {
+            Some(CrewLost)
+        } else if self.collapsed_for >= COLLAPSE_TURNS {
+            Some(Collapse)
+        } else {
+            self.objectives
+                .iter()
+                .find(|o| self.is_met(o))
+                .map(|o| o.outcome())
+        };
+        if let Some(outcome) = &self.outcome {
+            self.state.log(outcome.description());
+        }
+    }
+
+    pub fn is_met(&self, objective: &Objective) -> bool {
+        match objective {
+            Objective::FinalResearch(blueprints) => self.outpost.blueprints() >= *blueprints,
+            Objective::SectorCharted => {
+                self.sector.visited_sub_sectors_len() == self.sector.sub_sectors_len()
+            }
+            Objective::Survive(turns) => self.state.current_turn >= *turns,
+        }
+    }
+
+    pub fn summary(&self) -> Option<RunSummary> {
+        self.outcome.as_ref().map(|outcome| RunSummary {
+            outcome: outcome.clone(),
+            turns: self.state.current_turn,
+            crew: self.outpost.crew_len() + self.sector.mission_crew_len(),
+            casualties: self.outpost.cemetery_len()
+                + self
+                    .archive
+                    .iter()
+                    .map(|r| r.casualties.len())
+                    .sum::<usize>(),
+            missions: self.archive.len(),
+            blueprints: self.outpost.blueprints(),
+            visited_sub_sectors: self.sector.visited_sub_sectors_len(),
+            sub_sectors: self.sector.sub_sectors_len(),
+        })
     }
 
     pub fn increment_energy_level(&mut self, module_index: usize) {
@@ -108,6 +240,18 @@ impl Game {
         let id = self.outpost.module_id_by_index(module_index);
         self.outpost.decrement_energy_level(&id)
     }
+    pub fn raise_module_priority(&mut self, module_index: usize) {
+        let id = self.outpost.module_id_by_index(module_index);
+        self.outpost.raise_priority(&id)
+    }
+    pub fn lower_module_priority(&mut self, module_index: usize) {
+        let id = self.outpost.module_id_by_index(module_index);
+        self.outpost.lower_priority(&id)
+    }
+    pub fn repair_module(&mut self, module_index: usize) -> Result<(), ModuleRepairIssue> {
+        let id = self.outpost.module_id_by_index(module_index);
+        self.outpost.repair_module(&id)
+    }
     pub fn assign_crew_member_to_module(&mut self, crew_member_index: usize, module_index: usize) {
         let crew_member_id = self.outpost.crew_member_id_by_index(crew_member_index);
         let module_id = self.outpost.module_id_by_index(module_index);
@@ -120,21 +264,70 @@ impl Game {
     pub fn decrement_prepare_for_turns(&mut self) {
         self.outpost.decrement_prepare_for_turns()
     }
-    pub fn prepare_crew_member_for_mission(&mut self, crew_member_index: usize) {
+    pub fn prepare_crew_member_for_mission(
+        &mut self,
+        crew_member_index: usize,
+    ) -> Result<(), MissionPreparationIssue> {
         let crew_member_id = self.outpost.crew_member_id_by_index(crew_member_index);
         self.outpost
             .prepare_crew_member_for_mission(&crew_member_id)
     }
-    pub fn start_mission(&mut self, x: i32, y: i32, mission_index: usize) -> bool {
-        let mission_id = self.sector.missions_at(x, y)[mission_index].id();
-        if let Some(active_mission) = self
-            .outpost
-            .start_mission(self.sector.get_mission(&mission_id))
-        {
-            self.sector.set_active_mission(active_mission);
-            return true;
+    pub fn remove_crew_member_from_mission(&mut self, index: usize) {
+        self.outpost.remove_crew_member_from_mission(index)
+    }
+    pub fn move_crew_member_in_mission(&mut self, index: usize, offset: i32) -> usize {
+        self.outpost.move_crew_member_in_mission(index, offset)
+    }
+    pub fn describe_mission_preparation(
+        &self,
+        x: i32,
+        y: i32,
+        mission_index: usize,
+    ) -> Option<MissionPreparationDescription<'_>> {
+        let mission = *self.sector.missions_at(x, y).get(mission_index)?;
+        let distance = self.sector.distance_to(&mission.sub_sector_id);
+        let mut preparation =
+            self.outpost
+                .describe_mission_preparation(mission, distance, &self.state.config());
+        if self.sector.get_active_mission().is_some() {
+            preparation
+                .issues
+                .insert(0, MissionPreparationIssue::MissionInProgress);
         }
-        return false;
+        Some(preparation)
+    }
+    pub fn start_mission(
+        &mut self,
+        x: i32,
+        y: i32,
+        mission_index: usize,
+    ) -> Result<(), MissionPreparationIssue> {
+        // the ship and crew of the running mission would be lost
+        if self.sector.get_active_mission().is_some() {
+            return Err(MissionPreparationIssue::MissionInProgress);
+        }
+        let mission = *self
+            .sector
+            .missions_at(x, y)
+            .get(mission_index)
+            .ok_or(MissionPreparationIssue::UnknownMission)?;
+        let distance = self.sector.distance_to(&mission.sub_sector_id);
+        let active_mission = self.outpost.start_mission(mission, distance)?;
+        self.sector.set_active_mission(active_mission);
+        Ok(())
+    }
+    pub fn select_next_ship_for_mission(&mut self) {
+        self.outpost.select_next_ship_for_mission()
+    }
+    pub fn build_ship(&mut self, class_index: usize) -> Result<(), ShipBuildIssue> {
+        self.outpost.build_ship(ShipClass::ALL[class_index])
+    }
+    pub fn resolve_threat(&mut self, option_index: usize) {
+        self.outpost.resolve_threat(&mut self.state, option_index)
+    }
+    pub fn resolve_mission_event(&mut self, option_index: usize) {
+        self.sector
+            .resolve_mission_event(&mut self.state, option_index)
     }
 }
 
@@ -142,7 +335,7 @@ pub trait Entity {
     fn id(&self) -> &String;
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Storage<T>
 where
     T: Entity,
@@ -167,6 +360,9 @@ impl<T: Entity> Storage<T> {
     pub fn remove(&mut self, id: &String) -> Option<T> {
         self.data.remove(id)
     }
+    pub fn contains(&self, id: &String) -> bool {
+        self.data.contains_key(id)
+    }
 
     pub fn iter(&self) -> impl Iterator<Item = &T> {
         self.data.values()
@@ -206,7 +402,7 @@ impl<T: Entity> IndexMut<&String> for Storage<T> {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SortableStorage<T>
 where
     T: Entity,
@@ -305,3 +501,170 @@ impl Sub for AxialHexCoordinates {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::difficulty::Difficulty;
+    use super::outpost::MissionPreparationIssue;
+    use super::profile::Profile;
+    use super::resources::Resources;
+    use super::run::Objective;
+    use super::run::RunOutcome;
+    use super::scenario::Scenario;
+    use super::{Game, GameSetup};
+    use std::path::Path;
+
+    fn scenario(file: &str) -> Scenario {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("scenarios")
+            .join(file);
+        Scenario::load(&path).unwrap()
+    }
+
+    #[test]
+    fn setup_decides_difficulty_and_scenario() {
+        let setup = GameSetup {
+            seed: 1,
+            difficulty: Difficulty::Hard,
+            scenario: scenario("skeleton_crew.json"),
+        };
+        let game = Game::new(&setup, &Profile::default());
+
+        assert_eq!(Difficulty::Hard, game.state.difficulty);
+        assert_eq!("skeleton crew", game.state.scenario);
+        assert_eq!(2, game.outpost.crew_len());
+        assert_eq!(
+            Resources::living_space(2) + Resources::food(4) + Resources::water(2),
+            game.outpost.crew_upkeep()
+        );
+    }
+
+    #[test]
+    fn charting_the_sector_ends_the_run() {
+        let mut game = Game::new(&GameSetup::random(), &Profile::default());
+        for y in -1..2 {
+            let (min, max) = game.sector.bounds_at_y(y);
+            for x in min..max + 1 {
+                game.sector.visit(x, y);
+            }
+        }
+
+        game.finish_turn();
+        game.finish_turn();
+
+        assert_eq!(Some(RunOutcome::SectorCharted), game.outcome);
+        let summary = game.summary().unwrap();
+        assert_eq!(1, summary.turns);
+        assert_eq!(summary.sub_sectors, summary.visited_sub_sectors);
+    }
+
+    #[test]
+    fn holding_out_wins_the_holdout() {
+        let setup = GameSetup {
+            seed: 1,
+            difficulty: Difficulty::Normal,
+            scenario: scenario("holdout.json"),
+        };
+        let mut game = Game::new(&setup, &Profile::default());
+        assert_eq!(vec![Objective::Survive(40)], game.objectives);
+
+        game.state.current_turn = 40;
+        game.finish_turn();
+
+        assert_eq!(Some(RunOutcome::Survived), game.outcome);
+    }
+
+    #[test]
+    fn the_same_seed_replays_the_same_run() {
+        let play = |seed: &str| {
+            let mut setup = GameSetup::random();
+            setup.set_seed(seed).unwrap();
+            let mut game = Game::new(&setup, &Profile::default());
+            launch_mission(&mut game, 1, 0).unwrap();
+            for _ in 0..30 {
+                if game.sector.pending_mission_event().is_some() {
+                    game.resolve_mission_event(0);
+                }
+                if game.outpost.pending_threat().is_some() {
+                    game.resolve_threat(0);
+                }
+                game.finish_turn();
+            }
+            let board: Vec<String> = game
+                .sector
+                .mission_board()
+                .iter()
+                .map(|(c, m)| format!("{} {} {}", c.x, c.y, m.mission_type.to_string()))
+                .collect();
+            let archive: Vec<String> = game
+                .archive
+                .iter()
+                .map(|r| format!("{} {} {} {}", r.returned_at, r.x, r.y, r.title))
+                .collect();
+            (
+                game.state.seed.clone(),
+                game.state.logs.clone(),
+                board,
+                archive,
+            )
+        };
+
+        let first = play("AAAAAAAAACo=");
+        assert_eq!("AAAAAAAAACo=", first.0);
+        assert!(!first.1.is_empty());
+        assert!(!first.2.is_empty());
+        assert!(!first.3.is_empty());
+        assert_eq!(first, play("AAAAAAAAACo="));
+    }
+
+    #[test]
+    fn finished_turns_are_recorded() {
+        let mut game = Game::new(&GameSetup::random(), &Profile::default());
+        assert_eq!(1, game.state.history.len());
+
+        game.finish_turn();
+
+        let record = game.state.history.last().unwrap();
+        assert_eq!(2, game.state.history.len());
+        assert_eq!(1, record.turn);
+        assert_eq!(game.outpost.crew_len(), record.crew.total);
+        assert_eq!(game.outpost.resources(), &record.resources);
+    }
+
+    fn launch_mission(game: &mut Game, x: i32, y: i32) -> Result<(), MissionPreparationIssue> {
+        game.prepare_crew_member_for_mission(0)?;
+        game.increment_prepare_for_turns();
+        game.increment_prepare_for_turns();
+        game.select_next_ship_for_mission();
+        game.start_mission(x, y, 0)
+    }
+
+    #[test]
+    fn stale_mission_indices_are_refused() {
+        let mut game = Game::new(&GameSetup::random(), &Profile::default());
+
+        assert!(game.describe_mission_preparation(1, 0, 99).is_none());
+        assert_eq!(
+            Err(MissionPreparationIssue::UnknownMission),
+            game.start_mission(1, 0, 99)
+        );
+    }
+
+    #[test]
+    fn only_one_mission_runs_at_a_time() {
+        let mut game = Game::new(&GameSetup::random(), &Profile::default());
+        assert_eq!(Ok(()), launch_mission(&mut game, 1, 0));
+
+        let crew_len = game.outpost.crew_len();
+        assert_eq!(
+            Err(MissionPreparationIssue::MissionInProgress),
+            launch_mission(&mut game, 0, -1)
+        );
+        assert_eq!(
+            MissionPreparationIssue::MissionInProgress,
+            game.describe_mission_preparation(0, -1, 0).unwrap().issues[0]
+        );
+        assert_eq!(crew_len, game.outpost.crew_len());
+        assert_eq!(1, game.sector.mission_crew_len());
+    }
+}
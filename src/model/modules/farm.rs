@@ -11,11 +11,13 @@ use serde::{Deserialize, Serialize};
 use super::ModuleAssignmentDescription;
 use super::ModuleEnergyLevelDescription;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Farm {
     id: String,
     energy_level: i32,
     name: String,
+    #[serde(default = "default_priority")]
+    priority: ModulePriority,
 }
 
 impl Farm {
@@ -24,6 +26,7 @@ impl Farm {
             id: nanoid!(),
             energy_level: 1,
             name: name.to_string(),
+            priority: default_priority(),
         }
     }
 }
@@ -34,8 +37,17 @@ impl Entity for Farm {
     }
 }
 
+fn default_priority() -> ModulePriority {
+    ModulePriority::Mid
+}
+
 pub fn production_bonus(crew: &CrewMember) -> i32 {
-    crew.apply_mood((1.0 + (crew.stats.biology as f32 / 10.0)) / 4.0)
+    crew.apply_mood(base_production_bonus(crew.stats.biology))
+}
+
+/// the bonus of a crew member in a neutral mood
+fn base_production_bonus(biology: i32) -> f32 {
+    (1.0 + biology as f32 / 10.0) / 4.0
 }
 
 #[typetag::serde]
@@ -45,7 +57,10 @@ impl Module for Farm {
     }
 
     fn priority(&self) -> ModulePriority {
-        ModulePriority::Mid
+        self.priority.clone()
+    }
+    fn set_priority(&mut self, priority: ModulePriority) {
+        self.priority = priority
     }
 
     fn set_energy_level(&mut self, level: i32) {
@@ -57,10 +72,7 @@ impl Module for Farm {
     fn decrement_energy_level(&mut self) {
         self.set_energy_level(self.energy_level - 1)
     }
-    fn energy_levels<'a>(
-        &self,
-        crew: &Vec<&'a CrewMember>,
-    ) -> Vec<ModuleEnergyLevelDescription<'a>> {
+    fn energy_levels<'a>(&self, crew: &[&'a CrewMember]) -> Vec<ModuleEnergyLevelDescription<'a>> {
         let mut levels: Vec<ModuleEnergyLevelDescription> = vec![];
         for e in 1..4 {
             if e <= self.energy_level {
@@ -88,14 +100,14 @@ impl Module for Farm {
         }
         levels
     }
-    fn available_slots<'a>(&self, crew: &Vec<&'a CrewMember>) -> usize {
+    fn available_slots(&self, crew: &[&CrewMember]) -> usize {
         std::cmp::max(self.energy_level - crew.len() as i32, 0) as usize
     }
 
     fn consumption(&self) -> Resources {
         Resources::energy(self.energy_level) + Resources::water(self.energy_level)
     }
-    fn production(&self, crew: &Vec<&CrewMember>) -> Resources {
+    fn production(&self, crew: &[&CrewMember]) -> Resources {
         if crew.len() == 0 {
             return Resources::zero();
         }
@@ -109,25 +121,38 @@ impl Module for Farm {
     fn production_bonus(&self, crew: &CrewMember) -> Resources {
         Resources::food(production_bonus(crew))
     }
+    fn storage(&self) -> Resources {
+        Resources::zero()
+    }
+    fn docking_bays(&self) -> i32 {
+        0
+    }
+    fn shielding(&self) -> i32 {
+        0
+    }
+    fn defense(&self, _crew: &[&CrewMember]) -> i32 {
+        0
+    }
 
     fn finish_turn(&self) {}
+
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::model::{crew::CrewMember, stats::Stats};
 
-    use super::production_bonus;
+    use super::{base_production_bonus, production_bonus};
 
     #[test]
     fn calculate_production_bonus() {
         let assert_bonus = |expected: i32, biology: i32| {
-            let mut member = CrewMember::new("test");
-            member.stats = Stats::biology(biology);
-
             assert_eq!(
                 expected,
-                production_bonus(&member),
+                base_production_bonus(biology).ceil() as i32,
                 "{} biology should create production bonus {}",
                 biology,
                 expected
@@ -145,4 +170,18 @@ mod tests {
         assert_bonus(3, 90);
         assert_bonus(3, 100);
     }
+
+    #[test]
+    fn mood_scales_production_bonus() {
+        let mut member = CrewMember::new("test".to_string());
+        member.stats = Stats::biology(100);
+        // rested, fed and watered crew members are in a good mood, 1.8 times the bonus
+        assert_eq!(5, production_bonus(&member));
+
+        // a tired crew member is in a bad mood, 0.6 times the bonus
+        member.finish_turn();
+        member.eat();
+        member.drink();
+        assert_eq!(2, production_bonus(&member));
+    }
 }
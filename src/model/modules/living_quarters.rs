@@ -10,11 +10,13 @@ use serde::{Deserialize, Serialize};
 
 use super::ModuleEnergyLevelDescription;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct LivingQuarters {
     id: String,
     energy_level: i32,
     name: String,
+    #[serde(default = "default_priority")]
+    priority: ModulePriority,
 }
 
 impl LivingQuarters {
@@ -23,6 +25,7 @@ impl LivingQuarters {
             id: nanoid!(),
             energy_level: 1,
             name: name.to_string(),
+            priority: default_priority(),
         }
     }
 }
@@ -33,6 +36,10 @@ impl Entity for LivingQuarters {
     }
 }
 
+fn default_priority() -> ModulePriority {
+    ModulePriority::High
+}
+
 #[typetag::serde]
 impl Module for LivingQuarters {
     fn name(&self) -> &String {
@@ -40,7 +47,10 @@ impl Module for LivingQuarters {
     }
 
     fn priority(&self) -> ModulePriority {
-        ModulePriority::High
+        self.priority.clone()
+    }
+    fn set_priority(&mut self, priority: ModulePriority) {
+        self.priority = priority
     }
 
     fn set_energy_level(&mut self, level: i32) {
@@ -52,10 +62,7 @@ impl Module for LivingQuarters {
     fn decrement_energy_level(&mut self) {
         self.set_energy_level(self.energy_level - 1)
     }
-    fn energy_levels<'a>(
-        &self,
-        _crew: &Vec<&'a CrewMember>,
-    ) -> Vec<ModuleEnergyLevelDescription<'a>> {
+    fn energy_levels<'a>(&self, _crew: &[&'a CrewMember]) -> Vec<ModuleEnergyLevelDescription<'a>> {
         let mut levels: Vec<ModuleEnergyLevelDescription> = vec![];
         for e in 1..4 {
             if e <= self.energy_level {
@@ -76,19 +83,35 @@ impl Module for LivingQuarters {
         }
         levels
     }
-    fn available_slots<'a>(&self, _crew: &Vec<&'a CrewMember>) -> usize {
+    fn available_slots(&self, _crew: &[&CrewMember]) -> usize {
         0
     }
 
     fn consumption(&self) -> Resources {
         Resources::energy(self.energy_level)
     }
-    fn production(&self, _crew: &Vec<&CrewMember>) -> Resources {
+    fn production(&self, _crew: &[&CrewMember]) -> Resources {
         Resources::living_space(self.energy_level * 2)
     }
     fn production_bonus(&self, _crew: &CrewMember) -> Resources {
         Resources::zero()
     }
+    fn storage(&self) -> Resources {
+        Resources::zero()
+    }
+    fn docking_bays(&self) -> i32 {
+        0
+    }
+    fn shielding(&self) -> i32 {
+        0
+    }
+    fn defense(&self, _crew: &[&CrewMember]) -> i32 {
+        0
+    }
 
     fn finish_turn(&self) {}
+
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
 }
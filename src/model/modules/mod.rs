@@ -1,4 +1,6 @@
 use crate::model::resources::Resources;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 use super::{crew::CrewMember, Entity};
 
@@ -35,31 +37,93 @@ pub trait Module: Entity {
     fn name(&self) -> &String;
 
     fn priority(&self) -> ModulePriority;
+    fn set_priority(&mut self, priority: ModulePriority);
 
     fn set_energy_level(&mut self, level: i32);
     fn increment_energy_level(&mut self);
     fn decrement_energy_level(&mut self);
-    fn energy_levels<'a>(
-        &self,
-        crew: &Vec<&'a CrewMember>,
-    ) -> Vec<ModuleEnergyLevelDescription<'a>>;
-    fn available_slots<'a>(&self, crew: &Vec<&'a CrewMember>) -> usize;
+    fn energy_levels<'a>(&self, crew: &[&'a CrewMember]) -> Vec<ModuleEnergyLevelDescription<'a>>;
+    /// crew members the module can still take in
+    fn available_slots(&self, _crew: &[&CrewMember]) -> usize {
+        0
+    }
 
     fn consumption(&self) -> Resources;
-    fn production(&self, crew: &Vec<&CrewMember>) -> Resources;
-    fn production_bonus(&self, crew: &CrewMember) -> Resources;
+    fn production(&self, _crew: &[&CrewMember]) -> Resources {
+        Resources::zero()
+    }
+    fn production_bonus(&self, _crew: &CrewMember) -> Resources {
+        Resources::zero()
+    }
+    fn storage(&self) -> Resources {
+        Resources::zero()
+    }
+    fn docking_bays(&self) -> i32 {
+        0
+    }
+    /// percentage by which the module lowers the odds of a crisis
+    fn shielding(&self) -> i32 {
+        0
+    }
+    /// percentage by which the module's defenders reduce the damage of hostile threats
+    fn defense(&self, _crew: &[&CrewMember]) -> i32 {
+        0
+    }
+
+    fn finish_turn(&self) {}
 
-    fn finish_turn(&self);
+    /// a copy of the module, trait objects can't derive Clone
+    fn clone_box(&self) -> Box<dyn Module>;
+}
+
+impl Clone for Box<dyn Module> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ModulePriority {
     High,
     Mid,
     Low,
 }
 
+impl ModulePriority {
+    pub fn raise(&self) -> ModulePriority {
+        use ModulePriority::*;
+        match self {
+            High | Mid => High,
+            Low => Mid,
+        }
+    }
+    pub fn lower(&self) -> ModulePriority {
+        use ModulePriority::*;
+        match self {
+            High => Mid,
+            Mid | Low => Low,
+        }
+    }
+}
+
+impl fmt::Display for ModulePriority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ModulePriority::*;
+        match *self {
+            High => write!(f, "high"),
+            Mid => write!(f, "mid"),
+            Low => write!(f, "low"),
+        }
+    }
+}
+
+pub mod battery;
 pub mod farm;
+pub mod hangar;
 pub mod living_quarters;
 pub mod power_generator;
+pub mod security_post;
+pub mod shield_generator;
+pub mod silo;
 pub mod water_extractor;
+pub mod water_tank;
@@ -11,11 +11,13 @@ use serde::{Deserialize, Serialize};
 use super::ModuleAssignmentDescription;
 use super::ModuleEnergyLevelDescription;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PowerGenerator {
     id: String,
     energy_level: i32,
     name: String,
+    #[serde(default = "default_priority")]
+    priority: ModulePriority,
 }
 
 impl PowerGenerator {
@@ -24,6 +26,7 @@ impl PowerGenerator {
             id: nanoid!(),
             energy_level: 1,
             name: name.to_string(),
+            priority: default_priority(),
         }
     }
 }
@@ -34,6 +37,10 @@ impl Entity for PowerGenerator {
     }
 }
 
+fn default_priority() -> ModulePriority {
+    ModulePriority::High
+}
+
 pub fn production_bonus(crew: &CrewMember) -> i32 {
     crew.apply_mood((1.0 + (crew.stats.engineering as f32 / 10.0)) / 2.0)
 }
@@ -45,7 +52,10 @@ impl Module for PowerGenerator {
     }
 
     fn priority(&self) -> ModulePriority {
-        ModulePriority::High
+        self.priority.clone()
+    }
+    fn set_priority(&mut self, priority: ModulePriority) {
+        self.priority = priority
     }
 
     fn set_energy_level(&mut self, level: i32) {
@@ -57,10 +67,7 @@ impl Module for PowerGenerator {
     fn decrement_energy_level(&mut self) {
         self.set_energy_level(self.energy_level - 1)
     }
-    fn energy_levels<'a>(
-        &self,
-        crew: &Vec<&'a CrewMember>,
-    ) -> Vec<ModuleEnergyLevelDescription<'a>> {
+    fn energy_levels<'a>(&self, crew: &[&'a CrewMember]) -> Vec<ModuleEnergyLevelDescription<'a>> {
         let mut levels: Vec<ModuleEnergyLevelDescription> = vec![];
         for e in 1..4 {
             if e <= self.energy_level {
@@ -88,14 +95,14 @@ impl Module for PowerGenerator {
         }
         levels
     }
-    fn available_slots<'a>(&self, crew: &Vec<&'a CrewMember>) -> usize {
+    fn available_slots(&self, crew: &[&CrewMember]) -> usize {
         std::cmp::max(self.energy_level - crew.len() as i32, 0) as usize
     }
 
     fn consumption(&self) -> Resources {
         Resources::minerals(self.energy_level)
     }
-    fn production(&self, crew: &Vec<&CrewMember>) -> Resources {
+    fn production(&self, crew: &[&CrewMember]) -> Resources {
         if crew.len() == 0 {
             return Resources::zero();
         }
@@ -109,6 +116,22 @@ impl Module for PowerGenerator {
     fn production_bonus(&self, crew: &CrewMember) -> Resources {
         Resources::energy(production_bonus(crew))
     }
+    fn storage(&self) -> Resources {
+        Resources::zero()
+    }
+    fn docking_bays(&self) -> i32 {
+        0
+    }
+    fn shielding(&self) -> i32 {
+        0
+    }
+    fn defense(&self, _crew: &[&CrewMember]) -> i32 {
+        0
+    }
 
     fn finish_turn(&self) {}
+
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
 }
@@ -0,0 +1,114 @@
+use crate::model::crew::CrewMember;
+use crate::model::modules::Module;
+use crate::model::modules::ModulePriority;
+use crate::model::resources::Resources;
+use crate::model::Entity;
+use nanoid::nanoid;
+use serde::{Deserialize, Serialize};
+
+use super::ModuleAssignmentDescription;
+use super::ModuleEnergyLevelDescription;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SecurityPost {
+    id: String,
+    energy_level: i32,
+    name: String,
+    #[serde(default = "default_priority")]
+    priority: ModulePriority,
+}
+
+impl SecurityPost {
+    pub fn new(name: &str) -> Self {
+        Self {
+            id: nanoid!(),
+            energy_level: 1,
+            name: name.to_string(),
+            priority: default_priority(),
+        }
+    }
+}
+
+impl Entity for SecurityPost {
+    fn id(&self) -> &String {
+        &self.id
+    }
+}
+
+fn default_priority() -> ModulePriority {
+    ModulePriority::Mid
+}
+
+/// each guard on duty adds to the defense, trained soldiers far more than others
+pub fn defense_bonus(crew: &CrewMember) -> i32 {
+    5 + crew.effective_stats().military / 2
+}
+
+#[typetag::serde]
+impl Module for SecurityPost {
+    fn name(&self) -> &String {
+        &self.name
+    }
+
+    fn priority(&self) -> ModulePriority {
+        self.priority.clone()
+    }
+    fn set_priority(&mut self, priority: ModulePriority) {
+        self.priority = priority
+    }
+
+    fn set_energy_level(&mut self, level: i32) {
+        self.energy_level = level.clamp(0, 3)
+    }
+    fn increment_energy_level(&mut self) {
+        self.set_energy_level(self.energy_level + 1)
+    }
+    fn decrement_energy_level(&mut self) {
+        self.set_energy_level(self.energy_level - 1)
+    }
+    fn energy_levels<'a>(&self, crew: &[&'a CrewMember]) -> Vec<ModuleEnergyLevelDescription<'a>> {
+        let mut levels: Vec<ModuleEnergyLevelDescription> = vec![];
+        for e in 1..4 {
+            if e <= self.energy_level {
+                let assignment = crew
+                    .get((e - 1) as usize)
+                    .map(|c| ModuleAssignmentDescription {
+                        crew_name: c.name(),
+                        production_bonus: Resources::zero(),
+                    });
+
+                levels.push(ModuleEnergyLevelDescription {
+                    is_active: true,
+                    consumption: Resources::energy(1),
+                    production: Resources::zero(),
+                    assignment,
+                })
+            } else {
+                levels.push(ModuleEnergyLevelDescription {
+                    is_active: false,
+                    consumption: Resources::zero(),
+                    production: Resources::zero(),
+                    assignment: None,
+                })
+            }
+        }
+        levels
+    }
+    fn available_slots(&self, crew: &[&CrewMember]) -> usize {
+        std::cmp::max(self.energy_level - crew.len() as i32, 0) as usize
+    }
+
+    fn consumption(&self) -> Resources {
+        Resources::energy(self.energy_level)
+    }
+    fn defense(&self, crew: &[&CrewMember]) -> i32 {
+        crew.iter()
+            .take(self.energy_level as usize)
+            .map(|c| defense_bonus(c))
+            .sum()
+    }
+
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
+}
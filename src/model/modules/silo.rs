@@ -0,0 +1,99 @@
+use crate::model::crew::CrewMember;
+use crate::model::modules::Module;
+use crate::model::modules::ModulePriority;
+use crate::model::resources::Resources;
+use crate::model::Entity;
+use nanoid::nanoid;
+use serde::{Deserialize, Serialize};
+
+use super::ModuleEnergyLevelDescription;
+
+const BASE_CAPACITY: i32 = 10;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Silo {
+    id: String,
+    energy_level: i32,
+    name: String,
+    #[serde(default = "default_priority")]
+    priority: ModulePriority,
+}
+
+impl Silo {
+    pub fn new(name: &str) -> Self {
+        Self {
+            id: nanoid!(),
+            energy_level: 1,
+            name: name.to_string(),
+            priority: default_priority(),
+        }
+    }
+}
+
+impl Entity for Silo {
+    fn id(&self) -> &String {
+        &self.id
+    }
+}
+
+fn default_priority() -> ModulePriority {
+    ModulePriority::Low
+}
+
+#[typetag::serde]
+impl Module for Silo {
+    fn name(&self) -> &String {
+        &self.name
+    }
+
+    fn priority(&self) -> ModulePriority {
+        self.priority.clone()
+    }
+    fn set_priority(&mut self, priority: ModulePriority) {
+        self.priority = priority
+    }
+
+    fn set_energy_level(&mut self, level: i32) {
+        self.energy_level = level.clamp(0, 3)
+    }
+    fn increment_energy_level(&mut self) {
+        self.set_energy_level(self.energy_level + 1)
+    }
+    fn decrement_energy_level(&mut self) {
+        self.set_energy_level(self.energy_level - 1)
+    }
+    fn energy_levels<'a>(&self, _crew: &[&'a CrewMember]) -> Vec<ModuleEnergyLevelDescription<'a>> {
+        let mut levels: Vec<ModuleEnergyLevelDescription> = vec![];
+        for e in 1..4 {
+            if e <= self.energy_level {
+                levels.push(ModuleEnergyLevelDescription {
+                    is_active: true,
+                    consumption: Resources::energy(1),
+                    production: Resources::zero(),
+                    assignment: None,
+                })
+            } else {
+                levels.push(ModuleEnergyLevelDescription {
+                    is_active: false,
+                    consumption: Resources::zero(),
+                    production: Resources::zero(),
+                    assignment: None,
+                })
+            }
+        }
+        levels
+    }
+
+    fn consumption(&self) -> Resources {
+        Resources::energy(self.energy_level)
+    }
+    /// an unpowered silo still holds a base load, every energy level adds cooled space
+    fn storage(&self) -> Resources {
+        let capacity = BASE_CAPACITY + 10 * self.energy_level;
+        Resources::minerals(capacity) + Resources::food(capacity)
+    }
+
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
+}
@@ -1,36 +1,108 @@
-use crate::model::crew::CrewMember;
+use crate::model::crew::{team_stats, team_upkeep, CrewMember};
 use crate::model::modules::Module;
-use crate::model::resources::Resources;
+use crate::model::resources::{ResourceType, Resources};
+use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 use super::{
-    game_state::GameState,
+    difficulty::DifficultyConfig,
+    game_state::{CrewRecord, GameState, TurnRecord},
     modules::{ModuleEnergyLevelDescription, ModulePriority},
-    sector::{ActiveMission, Mission},
+    sector::{ActiveMission, Mission, MissionForecast, SectorType},
+    ship::{Ship, ShipClass},
     stats::Stats,
+    threats::{ThreatKind, ThreatOption, ThreatOutcome},
     Entity, SortableStorage, Storage,
 };
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Outpost {
     resources: Resources,
+    #[serde(default)]
+    energy_charge: i32,
     modules: SortableStorage<ModuleBox>,
     crew: Storage<CrewMember>,
     cemetery: Vec<CrewMember>,
+    #[serde(default = "Storage::new")]
+    ships: Storage<Ship>,
+    #[serde(default)]
+    blueprints: u32,
+    #[serde(default)]
+    pending_threat: Option<ThreatKind>,
     mission_preparation: MissionPreparation,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MissionPreparation {
     pub crew_ids: Vec<String>,
     pub turns: u16,
+    #[serde(default)]
+    pub ship_id: Option<String>,
 }
 
-pub struct MissionPreparationIssue {}
+#[derive(Debug, PartialEq, Eq)]
+pub enum MissionPreparationIssue {
+    DuplicateCrewMember(String),
+    NoCrew,
+    NoTurns,
+    NoShip,
+    TooManyCrew(usize),
+    MissingSupplies(Resources),
+    MissionInProgress,
+    UnknownMission,
+}
+
+impl fmt::Display for MissionPreparationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use MissionPreparationIssue::*;
+        match self {
+            DuplicateCrewMember(name) => write!(f, "{} is already part of the crew", name),
+            NoCrew => write!(f, "no crew assigned"),
+            NoTurns => write!(f, "no supplies packed, the mission needs at least one turn"),
+            NoShip => write!(f, "no ship assigned"),
+            TooManyCrew(capacity) => write!(f, "the ship only carries {} crew members", capacity),
+            MissingSupplies(_) => write!(f, "not enough supplies and fuel in stock"),
+            MissionInProgress => write!(f, "another mission is still under way"),
+            UnknownMission => write!(f, "the mission is no longer on offer"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ShipBuildIssue {
+    NoFreeDockingBay,
+    MissingMinerals(Resources),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ModuleRepairIssue {
+    NoDamage,
+    MissingMinerals(Resources),
+}
+
+impl fmt::Display for ModuleRepairIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ModuleRepairIssue::*;
+        match self {
+            NoDamage => write!(f, "the module is not damaged"),
+            MissingMinerals(_) => write!(f, "not enough minerals in stock for the repair"),
+        }
+    }
+}
 
-#[derive(Serialize, Deserialize)]
+/// condition points lost per active energy level and turn
+const WEAR_PER_LEVEL: i32 = 1;
+/// condition points restored per mineral
+const REPAIR_PER_MINERAL: i32 = 10;
+/// turns the overview looks ahead
+pub const FORECAST_TURNS: u32 = 10;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ModuleBox {
     module: Box<dyn Module>,
+    #[serde(default = "ModuleBox::full_condition")]
+    condition: i32,
 }
 
 impl ModuleBox {
@@ -41,7 +113,47 @@ impl ModuleBox {
         &mut self.module
     }
     pub fn new(module: Box<dyn Module>) -> Self {
-        Self { module }
+        Self {
+            module,
+            condition: ModuleBox::full_condition(),
+        }
+    }
+    fn full_condition() -> i32 {
+        100
+    }
+    pub fn condition(&self) -> i32 {
+        self.condition
+    }
+    pub fn is_broken(&self) -> bool {
+        self.condition <= 0
+    }
+    /// the share of its output a module still delivers in percent, a broken module stops
+    pub fn efficiency(&self) -> i32 {
+        match self.condition {
+            c if c >= 50 => 100,
+            c if c >= 25 => 75,
+            c if c > 0 => 50,
+            _ => 0,
+        }
+    }
+    pub fn damage(&mut self, amount: i32) {
+        self.condition = std::cmp::max(self.condition - amount, 0)
+    }
+    pub fn repair(&mut self, amount: i32) {
+        self.condition = std::cmp::min(self.condition + amount, ModuleBox::full_condition())
+    }
+    pub fn production(&self, crew: &Vec<&CrewMember>) -> Resources {
+        let efficiency = self.efficiency();
+        self.module
+            .production(crew)
+            .map(|_, v| v * efficiency / 100)
+    }
+    pub fn consumption(&self) -> Resources {
+        if self.is_broken() {
+            Resources::zero()
+        } else {
+            self.module.consumption()
+        }
     }
 }
 
@@ -80,7 +192,12 @@ pub struct MissionPreparationDescription<'a> {
     pub turns: u16,
     pub crew: Vec<MissionPreparationCrewMemberDescription>,
     pub mission: &'a Mission,
+    pub ship: Option<&'a Ship>,
+    pub travel_turns: u16,
     pub cost: Resources,
+    pub shortfall: Resources,
+    pub forecast: MissionForecast,
+    pub issues: Vec<MissionPreparationIssue>,
 }
 
 pub struct MissionPreparationCrewMemberDescription {
@@ -98,27 +215,52 @@ pub struct ModuleDescription<'a> {
     pub priority: ModulePriority,
     pub production: Resources,
     pub consumption: Resources,
+    pub storage: Resources,
+    pub condition: i32,
+    pub efficiency: i32,
     pub energy_levels: Vec<ModuleEnergyLevelDescription<'a>>,
 }
 
+/// what the coming turns bring if nothing changes, crises and missions aside
+pub struct OutpostForecast {
+    pub turns: u32,
+    /// the first turn a crew member goes hungry
+    pub food_runs_out: Option<u32>,
+    /// the first turn a crew member goes thirsty
+    pub water_runs_out: Option<u32>,
+    /// modules losing energy levels and the turn it first happens
+    pub cut_modules: Vec<(String, u32)>,
+    /// crew members dying of hunger or thirst and the turn it happens
+    pub deaths: Vec<(String, u32)>,
+}
+
+impl OutpostForecast {
+    pub fn is_calm(&self) -> bool {
+        self.food_runs_out.is_none()
+            && self.water_runs_out.is_none()
+            && self.cut_modules.is_empty()
+            && self.deaths.is_empty()
+    }
+}
+
 impl Outpost {
     pub fn new() -> Self {
         Self {
             crew: Storage::new(),
             cemetery: vec![],
             modules: SortableStorage::new(),
+            ships: Storage::new(),
+            blueprints: 0,
+            pending_threat: None,
 
-            resources: Resources {
-                energy: 0,
-                living_space: 0,
-                minerals: 10,
-                food: 10,
-                water: 10,
-            },
+            resources: Resources::minerals(10) + Resources::food(10) + Resources::water(10),
+
+            energy_charge: 0,
 
             mission_preparation: MissionPreparation {
                 crew_ids: vec![],
                 turns: 0,
+                ship_id: None,
             },
         }
     }
@@ -141,11 +283,15 @@ impl Outpost {
     }
     pub fn describe_module<'a>(&'a self, module: &'a Box<dyn Module>) -> ModuleDescription<'a> {
         let crew = self.crew_of_module(module);
+        let module_box = &self.modules[module.id()];
         ModuleDescription {
             name: module.name(),
             priority: module.priority(),
-            production: module.production(&crew),
-            consumption: module.consumption(),
+            production: module_box.production(&crew),
+            consumption: module_box.consumption(),
+            storage: module.storage(),
+            condition: module_box.condition(),
+            efficiency: module_box.efficiency(),
             energy_levels: module.energy_levels(&crew),
         }
     }
@@ -159,6 +305,36 @@ impl Outpost {
             .unwrap_mut()
             .decrement_energy_level()
     }
+    pub fn raise_priority(&mut self, module_id: &String) {
+        let module = self.modules[module_id].unwrap_mut();
+        module.set_priority(module.priority().raise())
+    }
+    pub fn lower_priority(&mut self, module_id: &String) {
+        let module = self.modules[module_id].unwrap_mut();
+        module.set_priority(module.priority().lower())
+    }
+    pub fn module_condition(&self, module_id: &String) -> i32 {
+        self.modules[module_id].condition()
+    }
+    /// the minerals needed to bring the module back to full condition
+    pub fn repair_cost(&self, module_id: &String) -> Resources {
+        let damage = ModuleBox::full_condition() - self.modules[module_id].condition();
+        Resources::minerals((damage + REPAIR_PER_MINERAL - 1) / REPAIR_PER_MINERAL)
+    }
+    pub fn repair_module(&mut self, module_id: &String) -> Result<(), ModuleRepairIssue> {
+        let cost = self.repair_cost(module_id);
+        if cost.is_zero() {
+            return Err(ModuleRepairIssue::NoDamage);
+        }
+        if !self.resources.covers(&cost) {
+            return Err(ModuleRepairIssue::MissingMinerals(
+                self.resources.shortfall(&cost),
+            ));
+        }
+        self.resources -= cost;
+        self.modules[module_id].repair(ModuleBox::full_condition());
+        Ok(())
+    }
 
     /** Crew */
     pub fn add_crew_member(&mut self, crew_member: CrewMember) {
@@ -196,6 +372,9 @@ impl Outpost {
     pub fn crew_len(&self) -> usize {
         self.crew.len()
     }
+    pub fn cemetery_len(&self) -> usize {
+        self.cemetery.len()
+    }
     pub fn assign_crew_member_to_module(&mut self, crew_member_id: &String, module_id: &String) {
         let crew_member = &mut self.crew[crew_member_id];
         crew_member.assign_to_module(module_id);
@@ -211,14 +390,29 @@ impl Outpost {
     pub fn describe_mission_preparation<'a>(
         &'a self,
         mission: &'a Mission,
+        distance: i32,
+        config: &DifficultyConfig,
     ) -> MissionPreparationDescription<'a> {
         let crew = self.describe_mission_preparation_crew();
-        let cost = self.mission_cost();
+        let ship = self.prepared_ship();
+        let cost = self.mission_cost(distance);
+        let shortfall = self.resources.shortfall(&cost);
+        let team = team_stats(
+            self.mission_preparation
+                .crew_ids
+                .iter()
+                .map(|crew_member_id| &self.crew[crew_member_id]),
+        );
         MissionPreparationDescription {
             turns: self.mission_preparation.turns,
             crew,
             mission,
+            ship,
+            travel_turns: ship.map(|s| s.travel_turns(distance)).unwrap_or(0),
             cost,
+            shortfall,
+            forecast: mission.mission_type.forecast(&team, config),
+            issues: self.mission_preparation_issues(distance),
         }
     }
     pub fn describe_mission_preparation_crew(
@@ -236,10 +430,36 @@ impl Outpost {
             })
             .collect()
     }
-    pub fn prepare_crew_member_for_mission(&mut self, crew_member_id: &String) {
+    pub fn prepare_crew_member_for_mission(
+        &mut self,
+        crew_member_id: &String,
+    ) -> Result<(), MissionPreparationIssue> {
+        if self.is_prepared_for_mission(crew_member_id) {
+            let name = self.crew[crew_member_id].name().clone();
+            return Err(MissionPreparationIssue::DuplicateCrewMember(name));
+        }
         self.mission_preparation
             .crew_ids
             .push(crew_member_id.clone());
+        Ok(())
+    }
+    pub fn is_prepared_for_mission(&self, crew_member_id: &String) -> bool {
+        self.mission_preparation.crew_ids.contains(crew_member_id)
+    }
+    pub fn remove_crew_member_from_mission(&mut self, index: usize) {
+        if index < self.mission_preparation.crew_ids.len() {
+            self.mission_preparation.crew_ids.remove(index);
+        }
+    }
+    /// swaps the prepared crew member with its neighbour, returns the new position
+    pub fn move_crew_member_in_mission(&mut self, index: usize, offset: i32) -> usize {
+        let crew_ids = &mut self.mission_preparation.crew_ids;
+        let target = index as i32 + offset;
+        if index >= crew_ids.len() || target < 0 || target as usize >= crew_ids.len() {
+            return index;
+        }
+        crew_ids.swap(index, target as usize);
+        target as usize
     }
     pub fn increment_prepare_for_turns(&mut self) {
         self.mission_preparation.turns += 1;
@@ -249,8 +469,36 @@ impl Outpost {
             self.mission_preparation.turns -= 1;
         }
     }
-    pub fn mission_cost(&self) -> Resources {
-        let mut cost = self
+    pub fn prepared_ship(&self) -> Option<&Ship> {
+        self.mission_preparation
+            .ship_id
+            .as_ref()
+            .filter(|ship_id| self.ships.contains(ship_id))
+            .map(|ship_id| &self.ships[ship_id])
+    }
+    /// assigns the next docked ship to the mission
+    pub fn select_next_ship_for_mission(&mut self) {
+        let ships = self.ships();
+        let next = match self.prepared_ship() {
+            Some(ship) => ships
+                .iter()
+                .position(|s| s.id() == ship.id())
+                .map(|i| (i + 1) % ships.len())
+                .unwrap_or(0),
+            None => 0,
+        };
+        self.mission_preparation.ship_id = ships.get(next).map(|s| s.id().clone());
+    }
+    /// the supplies for the whole crew and the fuel for the prepared ship
+    pub fn mission_cost(&self, distance: i32) -> Resources {
+        let fuel = self
+            .prepared_ship()
+            .map(|s| s.fuel(distance))
+            .unwrap_or_else(Resources::zero);
+        self.mission_supplies() + fuel
+    }
+    pub fn mission_supplies(&self) -> Resources {
+        let cost = self
             .mission_preparation
             .crew_ids
             .iter()
@@ -260,18 +508,50 @@ impl Outpost {
             })
             .reduce(|a, b| a + b)
             .unwrap_or_else(Resources::zero);
-        cost.living_space = 0;
-        cost.energy = 0;
-        cost
+        cost.stocks()
     }
-    pub fn start_mission(&mut self, mission: &Mission) -> Option<ActiveMission> {
-        let cost = self.mission_cost();
-
-        // pay mission cost if possible
-        if cost > self.resources {
-            return None;
+    pub fn mission_preparation_issues(&self, distance: i32) -> Vec<MissionPreparationIssue> {
+        use MissionPreparationIssue::*;
+        let crew_ids = &self.mission_preparation.crew_ids;
+        let mut issues = vec![];
+        if crew_ids.is_empty() {
+            issues.push(NoCrew);
+        }
+        for (i, crew_member_id) in crew_ids.iter().enumerate() {
+            if crew_ids[..i].contains(crew_member_id) {
+                issues.push(DuplicateCrewMember(
+                    self.crew[crew_member_id].name().clone(),
+                ));
+            }
+        }
+        if self.mission_preparation.turns == 0 {
+            issues.push(NoTurns);
+        }
+        match self.prepared_ship() {
+            Some(ship) if crew_ids.len() > ship.crew_capacity() => {
+                issues.push(TooManyCrew(ship.crew_capacity()))
+            }
+            Some(_) => (),
+            None => issues.push(NoShip),
+        }
+        let shortfall = self.resources.shortfall(&self.mission_cost(distance));
+        if !shortfall.is_zero() {
+            issues.push(MissingSupplies(shortfall));
+        }
+        issues
+    }
+    pub fn start_mission(
+        &mut self,
+        mission: &Mission,
+        distance: i32,
+    ) -> Result<ActiveMission, MissionPreparationIssue> {
+        if let Some(issue) = self.mission_preparation_issues(distance).into_iter().next() {
+            return Err(issue);
         }
-        self.resources -= cost.clone();
+
+        // pay mission cost, the fuel is burnt on the way
+        let supplies = self.mission_supplies();
+        self.resources -= self.mission_cost(distance);
 
         // remove crew from outpost and assign to mission
         let mut crew = vec![];
@@ -283,59 +563,357 @@ impl Outpost {
         }
 
         // create mission and reset preparation
-        let active_mission = ActiveMission::new(mission.id(), cost, crew);
+        let ship_id = self.mission_preparation.ship_id.take().unwrap();
+        let ship = self.ships.remove(&ship_id).unwrap();
+        let mut active_mission = ActiveMission::new(mission.id(), supplies, crew);
+        active_mission.distance = ship.travel_turns(distance);
+        active_mission.ship = Some(ship);
         self.mission_preparation = MissionPreparation {
             crew_ids: vec![],
             turns: 0,
+            ship_id: None,
         };
-        Some(active_mission)
+        Ok(active_mission)
+    }
+
+    /// the crew moves back in and unloads the cargo together with the leftover supplies
+    pub fn receive_mission(&mut self, state: &mut GameState, active_mission: ActiveMission) {
+        let returned = active_mission.disband();
+        state.log(&format!(
+            "{} crew members returned from a mission",
+            returned.crew.len()
+        ));
+        for mut crew_member in returned.crew {
+            crew_member.unassign_from_mission();
+            self.crew.add(crew_member);
+        }
+        if let Some(ship) = returned.ship {
+            self.ships.add(ship);
+        }
+        self.blueprints += returned.blueprints;
+        self.resources += returned.resources.stocks();
+    }
+
+    pub fn blueprints(&self) -> u32 {
+        self.blueprints
+    }
+
+    /** Ships */
+    pub fn ships(&self) -> Vec<&Ship> {
+        let mut ships: Vec<&Ship> = self.ships.iter().collect();
+        ships.sort_by_key(|s| s.name());
+        ships
+    }
+    pub fn add_ship(&mut self, ship: Ship) {
+        self.ships.add(ship)
+    }
+    pub fn docking_bays(&self) -> i32 {
+        self.modules
+            .iter()
+            .filter(|m| !m.is_broken())
+            .map(|m| m.unwrap().docking_bays())
+            .sum()
+    }
+    pub fn build_ship(&mut self, class: ShipClass) -> Result<(), ShipBuildIssue> {
+        if self.ships.len() as i32 >= self.docking_bays() {
+            return Err(ShipBuildIssue::NoFreeDockingBay);
+        }
+        let cost = class.metadata().build_cost;
+        if !self.resources.covers(&cost) {
+            return Err(ShipBuildIssue::MissingMinerals(
+                self.resources.shortfall(&cost),
+            ));
+        }
+        self.resources -= cost;
+        let name = format!("{} {}", class.metadata().name, self.ships.len() + 1);
+        self.ships.add(Ship::new(&name, class));
+        Ok(())
     }
 
     /** Resources */
     pub fn resources(&self) -> &Resources {
         &self.resources
     }
+    pub fn set_resources(&mut self, resources: Resources) {
+        self.resources = resources
+    }
+    pub fn add_resources(&mut self, resources: Resources) {
+        self.resources += resources
+    }
+    pub fn energy_charge(&self) -> i32 {
+        self.energy_charge
+    }
     pub fn production(&self) -> Resources {
         self.modules
             .iter()
-            .map(|m| m.unwrap())
-            .map(|m| m.production(&self.crew_of_module(m)))
+            .map(|m| m.production(&self.crew_of_module(m.unwrap())))
             .reduce(|a, b| a + b)
             .unwrap_or_else(Resources::zero)
     }
     pub fn consumption(&self) -> Resources {
         self.modules
             .iter()
-            .map(|m| m.unwrap())
             .map(|m| m.consumption())
             .reduce(|a, b| a + b)
             .unwrap_or_else(Resources::zero)
     }
+    pub fn storage(&self) -> Resources {
+        self.modules
+            .iter()
+            .map(|m| m.unwrap().storage())
+            .fold(Resources::base_storage(), |a, b| a + b)
+    }
     pub fn crew_upkeep(&self) -> Resources {
-        let len = self.crew.len() as i32;
-        Resources {
-            energy: 0,
-            living_space: len,
-            minerals: 0,
-            food: len,
-            water: len,
+        team_upkeep(self.crew.iter())
+    }
+    /// an outpost that neither generates energy nor fills any stock has collapsed
+    pub fn is_collapsed(&self) -> bool {
+        let production = self.production();
+        production[ResourceType::Energy] == 0 && production.stocks().is_zero()
+    }
+
+    /** History */
+    pub fn turn_record(&self, turn: u32) -> TurnRecord {
+        let count = |f: fn(&CrewMember) -> bool| self.crew.iter().filter(|c| f(c)).count();
+        let health = if self.crew.len() == 0 {
+            0
+        } else {
+            self.crew.iter().map(|c| c.health()).sum::<i32>() / self.crew.len() as i32
+        };
+        TurnRecord {
+            turn,
+            resources: self.resources.clone(),
+            energy_charge: self.energy_charge,
+            crew: CrewRecord {
+                total: self.crew.len(),
+                hungry: count(CrewMember::is_hungry),
+                thirsty: count(CrewMember::is_thirsty),
+                tired: count(CrewMember::is_tired),
+                health,
+            },
+            active_modules: self
+                .modules
+                .iter()
+                .filter(|m| {
+                    !m.is_broken() && m.unwrap().energy_levels(&[]).iter().any(|l| l.is_active)
+                })
+                .map(|m| m.id().clone())
+                .collect(),
         }
     }
 
+    /** Forecast */
+    /// plays the next turns on a copy of the outpost, the outpost itself is left untouched
+    pub fn forecast(&self, turns: u32) -> OutpostForecast {
+        let mut outpost = self.clone();
+        // the logs of the simulated turns are thrown away
+        let mut state = GameState::new(0);
+        let mut forecast = OutpostForecast {
+            turns,
+            food_runs_out: None,
+            water_runs_out: None,
+            cut_modules: vec![],
+            deaths: vec![],
+        };
+        let active_levels = |o: &Outpost| -> Vec<(String, usize)> {
+            o.modules
+                .iter()
+                .map(|m| {
+                    let levels = m.unwrap().energy_levels(&[]);
+                    let active = levels.iter().filter(|l| l.is_active).count();
+                    (m.id().clone(), active)
+                })
+                .collect()
+        };
+
+        for turn in 1..turns + 1 {
+            let levels_before = active_levels(&outpost);
+            let crew_before: Vec<(String, String)> = outpost
+                .crew
+                .iter()
+                .map(|c| (c.id().clone(), c.name().clone()))
+                .collect();
+
+            outpost.finish_turn(&mut state);
+
+            for (id, active) in active_levels(&outpost) {
+                let was_active = levels_before
+                    .iter()
+                    .find(|(i, _)| *i == id)
+                    .map(|(_, a)| *a)
+                    .unwrap_or(0);
+                let name = outpost.modules[&id].unwrap().name().clone();
+                if active < was_active && !forecast.cut_modules.iter().any(|(n, _)| *n == name) {
+                    forecast.cut_modules.push((name, turn));
+                }
+            }
+            for (id, name) in crew_before {
+                if !outpost.crew.contains(&id) {
+                    forecast.deaths.push((name, turn));
+                }
+            }
+            if forecast.food_runs_out.is_none() && outpost.crew.iter().any(|c| c.is_hungry()) {
+                forecast.food_runs_out = Some(turn);
+            }
+            if forecast.water_runs_out.is_none() && outpost.crew.iter().any(|c| c.is_thirsty()) {
+                forecast.water_runs_out = Some(turn);
+            }
+        }
+        forecast
+    }
+
     /** Finish turn */
-    pub fn finish_turn(&mut self, _state: &mut GameState) {
-        self.store_production();
+    pub fn finish_turn(&mut self, state: &mut GameState) {
+        self.store_production(state);
 
         for c in self.crew.iter_mut() {
             c.finish_turn();
-            if !c.is_alive() {
-                self.cemetery.push(c.clone());
-            }
         }
-        self.crew.retain(|c| c.is_alive());
+        self.bury_dead(state);
 
         self.support_modules();
         self.support_crew();
+        self.charge_batteries();
+        self.maintain_modules(state);
+    }
+
+    /// running modules wear down, assigned engineers keep them in shape
+    fn maintain_modules(&mut self, state: &mut GameState) {
+        for m in self.modules.iter_mut() {
+            let active_levels = m
+                .unwrap()
+                .energy_levels(&[])
+                .iter()
+                .filter(|l| l.is_active)
+                .count() as i32;
+            let repairs: i32 = self
+                .crew
+                .iter()
+                .filter(|c| c.is_assigned_to_module(m.unwrap()))
+                .map(|c| c.effective_stats().engineering / 10)
+                .sum();
+            let was_broken = m.is_broken();
+            m.damage(active_levels * WEAR_PER_LEVEL);
+            m.repair(repairs);
+            if m.is_broken() && !was_broken {
+                state.log(&format!("the {} broke down", m.unwrap().name()));
+            }
+        }
+    }
+
+    fn bury_dead(&mut self, state: &mut GameState) {
+        for c in self.crew.iter().filter(|c| !c.is_alive()) {
+            state.log(&format!("{} died", c.name()));
+            self.cemetery.push(c.clone());
+        }
+        self.crew.retain(|c| c.is_alive());
+        let crew = &self.crew;
+        self.mission_preparation
+            .crew_ids
+            .retain(|crew_member_id| crew.contains(crew_member_id));
+    }
+
+    /** Threats */
+    pub fn pending_threat(&self) -> Option<&ThreatKind> {
+        self.pending_threat.as_ref()
+    }
+    pub fn shielding(&self) -> i32 {
+        self.modules
+            .iter()
+            .filter(|m| !m.is_broken())
+            .map(|m| m.unwrap().shielding())
+            .sum()
+    }
+    /// percentage of the damage from hostile threats that the guards prevent
+    pub fn defense(&self) -> i32 {
+        self.modules
+            .iter()
+            .filter(|m| !m.is_broken())
+            .map(|m| m.unwrap().defense(&self.crew_of_module(m.unwrap())))
+            .sum()
+    }
+    /// rolls this turn's crisis, severe ones wait for a response
+    pub fn roll_threats(&mut self, state: &mut GameState, sector_type: &SectorType) {
+        if self.pending_threat.is_some() {
+            return;
+        }
+        if let Some(threat) = ThreatKind::draw(state, sector_type, self.shielding()) {
+            state.log(threat.description());
+            if threat.requires_decision() {
+                self.pending_threat = Some(threat);
+            } else {
+                let outcomes = self.threat_outcomes(&threat, &threat.options()[0]);
+                self.apply_threat_outcomes(state, &outcomes);
+            }
+        }
+    }
+    pub fn resolve_threat(&mut self, state: &mut GameState, option_index: usize) {
+        let threat = match &self.pending_threat {
+            Some(threat) => threat.clone(),
+            None => return,
+        };
+        let options = threat.options();
+        // an unknown choice leaves the crisis pending
+        let option = match options.get(option_index) {
+            Some(option) => option,
+            None => return,
+        };
+        self.pending_threat = None;
+        state.log(&format!("the crew decided to {}", option.description));
+        let outcomes = self.threat_outcomes(&threat, option);
+        self.apply_threat_outcomes(state, &outcomes);
+    }
+    /// the outcomes of the chosen option, the guards soften the blow of hostile threats
+    fn threat_outcomes(&self, threat: &ThreatKind, option: &ThreatOption) -> Vec<ThreatOutcome> {
+        let outcomes = option.outcomes.clone();
+        if !threat.is_hostile() {
+            return outcomes;
+        }
+        let defense = self.defense();
+        outcomes
+            .into_iter()
+            .filter_map(|o| o.defended(defense))
+            .collect()
+    }
+    fn apply_threat_outcomes(&mut self, state: &mut GameState, outcomes: &Vec<ThreatOutcome>) {
+        use ThreatOutcome::*;
+        for outcome in outcomes {
+            match outcome {
+                DamageModule(amount) => {
+                    let active: Vec<&String> = self
+                        .modules
+                        .iter()
+                        .filter(|m| m.unwrap().energy_levels(&[]).iter().any(|l| l.is_active))
+                        .map(|m| m.id())
+                        .collect();
+                    if let Some(module_id) = active.choose(&mut state.rng).cloned().cloned() {
+                        let module = &mut self.modules[&module_id];
+                        module.damage(*amount);
+                        state.log(&format!("the {} was damaged", module.unwrap().name()));
+                    }
+                }
+                Loss(resources) => self.resources -= resources.clone(),
+                DrainBatteries => self.energy_charge = 0,
+                Injury(amount) => {
+                    // ids are random, sort by name so the same seed injures the same crew member
+                    let mut crew: Vec<&CrewMember> = self.crew.iter().collect();
+                    crew.sort_by(|a, b| a.name().cmp(b.name()));
+                    if let Some(crew_member_id) =
+                        crew.choose(&mut state.rng).map(|c| c.id().clone())
+                    {
+                        let crew_member = &mut self.crew[&crew_member_id];
+                        crew_member.injure(*amount);
+                        state.log(&format!("{} was injured", crew_member.name()));
+                    }
+                }
+                Outbreak(amount) => {
+                    for c in self.crew.iter_mut() {
+                        c.injure(*amount);
+                    }
+                }
+            }
+        }
+        self.bury_dead(state);
     }
 
     fn sort_modules_asc_by_priority(&mut self) {
@@ -344,45 +922,72 @@ impl Outpost {
             let right = b.unwrap();
             let priority_cmp = left.priority().cmp(&right.priority());
             match priority_cmp {
-                std::cmp::Ordering::Equal => left.consumption().cmp(&right.consumption()),
+                std::cmp::Ordering::Equal => {
+                    left.consumption().iter().cmp(right.consumption().iter())
+                }
                 std::cmp::Ordering::Less => std::cmp::Ordering::Greater,
                 std::cmp::Ordering::Greater => std::cmp::Ordering::Less,
             }
         });
     }
 
-    fn store_production(&mut self) {
+    fn store_production(&mut self, state: &mut GameState) {
+        // stock already held survives a shrinking capacity, e.g. an unpowered silo
+        let capacity = self
+            .storage()
+            .zip(&self.resources.stocks(), |_, capacity, held| {
+                std::cmp::max(capacity, held)
+            });
         self.resources += self.production();
+
+        // new stock beyond the storage capacity is lost
+        let overflow = self
+            .resources
+            .stocks()
+            .zip(&capacity, |_, amount, capacity| {
+                std::cmp::max(amount - capacity, 0)
+            });
+        if !overflow.is_zero() {
+            let lost: Vec<String> = overflow
+                .iter()
+                .filter(|(_, v)| *v > 0)
+                .map(|(t, v)| format!("{} {}", v, t.metadata().name))
+                .collect();
+            state.log(&format!("storage full, lost {}", lost.join(", ")));
+            self.resources -= overflow;
+        }
     }
 
     fn support_crew(&mut self) {
-        let mut available_space = self.resources.living_space;
-        let mut available_energy = self.resources.energy;
+        use ResourceType::*;
+        let mut available_space = self.resources[LivingSpace];
         for c in self.crew.iter_mut() {
             let upkeep = c.upkeep();
 
-            if upkeep.food == 0 && upkeep.minerals == 0 {
+            if upkeep[Food] == 0 && upkeep[Minerals] == 0 {
                 c.eat()
-            } else if self.resources.food >= upkeep.food
-                && self.resources.minerals >= upkeep.minerals
+            } else if self.resources[Food] >= upkeep[Food]
+                && self.resources[Minerals] >= upkeep[Minerals]
             {
-                self.resources.food -= upkeep.food;
-                self.resources.minerals -= upkeep.minerals;
+                self.resources[Food] -= upkeep[Food];
+                self.resources[Minerals] -= upkeep[Minerals];
                 c.eat();
             }
 
-            if upkeep.water == 0 && upkeep.energy == 0 {
+            if upkeep[Water] == 0 && upkeep[Energy] == 0 {
                 c.drink()
-            } else if self.resources.water >= upkeep.water && available_energy >= upkeep.energy {
-                self.resources.water -= upkeep.water;
-                available_energy -= upkeep.energy;
+            } else if self.resources[Water] >= upkeep[Water]
+                && self.resources[Energy] >= upkeep[Energy]
+            {
+                self.resources[Water] -= upkeep[Water];
+                self.resources[Energy] -= upkeep[Energy];
                 c.drink();
             }
 
-            if upkeep.living_space == 0 {
+            if upkeep[LivingSpace] == 0 {
                 c.rest()
-            } else if available_space >= upkeep.living_space {
-                available_space -= upkeep.living_space;
+            } else if available_space >= upkeep[LivingSpace] {
+                available_space -= upkeep[LivingSpace];
                 c.rest();
             }
         }
@@ -391,34 +996,49 @@ impl Outpost {
     fn support_modules(&mut self) {
         loop {
             let consumption = self.consumption();
-            let can_self_sustain = self.resources.energy >= consumption.energy
-                && self.resources.living_space >= consumption.living_space
-                && self.resources.minerals >= consumption.minerals
-                && self.resources.food >= consumption.food
-                && self.resources.water >= consumption.water;
+            let available = self.available_resources();
+            let can_self_sustain = available.covers(&consumption);
             if can_self_sustain {
                 break;
             }
             self.cut_energy(consumption);
         }
-        self.resources -= self.consumption();
+        let consumption = self.consumption();
+
+        // a deficit is drawn from the batteries, the rest of the energy is left to the crew
+        let left = self.resources[ResourceType::Energy] - consumption[ResourceType::Energy];
+        self.energy_charge = std::cmp::max(self.energy_charge + std::cmp::min(left, 0), 0);
+        self.resources[ResourceType::Energy] = std::cmp::max(left, 0);
+
+        self.resources -= consumption;
+    }
+
+    /// whatever energy the modules and the crew left over charges the batteries
+    fn charge_batteries(&mut self) {
+        // a charge beyond the capacity of a cut battery is kept, it just takes no more
+        let capacity = std::cmp::max(self.storage()[ResourceType::Energy], self.energy_charge);
+        self.energy_charge = std::cmp::min(
+            self.energy_charge + self.resources[ResourceType::Energy],
+            capacity,
+        );
+        self.resources[ResourceType::Energy] = 0;
+    }
+
+    fn available_resources(&self) -> Resources {
+        self.resources.clone() + Resources::energy(self.energy_charge)
     }
 
     fn cut_energy(&mut self, consumption: Resources) {
         // run over all modules starting with lowest priority
         self.sort_modules_asc_by_priority();
+        let available = self.available_resources();
         for m in self.modules.iter_mut() {
             // find out if this module is a relevant consumer
-            let module = m.unwrap_mut();
-            let delta = consumption.clone() - self.resources.clone();
-            let consumption = module.consumption();
-            let module_is_relevant = (delta.energy > 0 && consumption.energy > 0)
-                || (delta.living_space > 0 && consumption.living_space > 0)
-                || (delta.minerals > 0 && consumption.minerals > 0)
-                || (delta.food > 0 && consumption.food > 0)
-                || (delta.water > 0 && consumption.water > 0);
+            let delta = available.shortfall(&consumption);
+            let consumption = m.consumption();
+            let module_is_relevant = consumption.iter().any(|(t, v)| delta[t] > 0 && v > 0);
             if module_is_relevant {
-                module.decrement_energy_level();
+                m.unwrap_mut().decrement_energy_level();
                 return;
             }
         }
@@ -427,80 +1047,600 @@ impl Outpost {
 
 #[cfg(test)]
 mod tests {
+    use crate::model::crew::CrewMember;
+    use crate::model::difficulty::Difficulty;
+    use crate::model::game_state::GameState;
+    use crate::model::modules::battery::Battery;
     use crate::model::modules::farm::Farm;
-    use crate::model::modules::mine::Mine;
+    use crate::model::modules::hangar::Hangar;
+    use crate::model::modules::power_generator::PowerGenerator;
+    use crate::model::modules::security_post::SecurityPost;
+    use crate::model::modules::shield_generator::ShieldGenerator;
+    use crate::model::modules::silo::Silo;
     use crate::model::modules::water_extractor::WaterExtractor;
-    use crate::model::modules::Module;
-    use crate::model::outpost::Outpost;
+    use crate::model::modules::water_tank::WaterTank;
+    use crate::model::modules::{Module, ModulePriority};
+    use crate::model::outpost::{
+        MissionPreparationIssue, ModuleRepairIssue, Outpost, ShipBuildIssue,
+    };
+    use crate::model::resources::ResourceType::*;
+    use crate::model::resources::Resources;
+    use crate::model::sector::{ActiveMission, Mission, MissionType};
+    use crate::model::ship::{Ship, ShipClass};
+    use crate::model::stats::Stats;
+    use crate::model::threats::{ThreatKind, ThreatOutcome};
+    use crate::model::Entity;
+
+    fn consumption_of(o: &Outpost, name: &str) -> i32 {
+        o.modules()
+            .iter()
+            .find(|m| m.name() == name)
+            .unwrap()
+            .consumption()[Energy]
+    }
 
     #[test]
     fn finish_turn_stores_production() {
         let mut o = Outpost::new();
-        assert_eq!(10, o.resources.minerals);
-        assert_eq!(10, o.resources.food);
-        assert_eq!(10, o.resources.water);
+        assert_eq!(10, o.resources[Minerals]);
+        assert_eq!(10, o.resources[Food]);
+        assert_eq!(10, o.resources[Water]);
+
+        let power = Box::new(PowerGenerator::new("power"));
+        let power_id = power.id().clone();
+        o.add_module(power);
+        let farm = Box::new(Farm::new("farm"));
+        let farm_id = farm.id().clone();
+        o.add_module(farm);
 
-        o.crew.clear();
-        o.modules.push(Box::new(Mine::new("mine1")));
-        o.modules.push(Box::new(Mine::new("mine2")));
-        o.modules.push(Box::new(Farm::new("farm1")));
-        o.modules
-            .push(Box::new(WaterExtractor::new("water_extractor1")));
+        let a = CrewMember::new("a".to_string());
+        let a_id = a.id().clone();
+        o.add_crew_member(a);
+        o.assign_crew_member_to_module(&a_id, &power_id);
+        let b = CrewMember::new("b".to_string());
+        let b_id = b.id().clone();
+        o.add_crew_member(b);
+        o.assign_crew_member_to_module(&b_id, &farm_id);
 
-        o.finish_turn();
+        o.finish_turn(&mut GameState::new(0));
 
-        assert_eq!(12, o.resources.minerals);
-        assert_eq!(11, o.resources.food);
-        assert_eq!(11, o.resources.water);
+        // the generator burns a mineral
+        assert_eq!(9, o.resources[Minerals]);
+        // the farm grows 1 plus 1 from its farmer, the crew eats 2
+        assert_eq!(10, o.resources[Food]);
+        // the farm uses 1 water, the crew drinks 2
+        assert_eq!(7, o.resources[Water]);
     }
 
     #[test]
     fn finish_turn_consumes_crew_upkeep() {
         let mut o = Outpost::new();
-        assert_eq!(10, o.resources.food);
-        assert_eq!(10, o.resources.water);
+        for name in ["a", "b", "c", "d"] {
+            o.add_crew_member(CrewMember::new(name.to_string()));
+        }
+        assert_eq!(10, o.resources[Food]);
+        assert_eq!(10, o.resources[Water]);
+
+        o.finish_turn(&mut GameState::new(0));
+
+        assert_eq!(6, o.resources[Food]);
+        assert_eq!(6, o.resources[Water]);
+    }
+
+    #[test]
+    fn forecast_predicts_hunger_and_deaths() {
+        let mut o = Outpost::new();
+        for name in ["a", "b", "c", "d"] {
+            o.add_crew_member(CrewMember::new(name.to_string()));
+        }
+
+        let forecast = o.forecast(6);
+
+        assert_eq!(Some(3), forecast.food_runs_out);
+        assert_eq!(Some(3), forecast.water_runs_out);
+        assert_eq!(2, forecast.deaths.len());
+        assert!(forecast.deaths.iter().all(|(_, turn)| *turn == 6));
+        // the outpost itself is untouched
+        assert_eq!(10, o.resources[Food]);
+        assert_eq!(4, o.crew_len());
+    }
+
+    #[test]
+    fn forecast_predicts_module_cuts() {
+        let mut o = Outpost::new();
+        let mut power = PowerGenerator::new("power");
+        power.set_energy_level(0);
+        o.add_module(Box::new(power));
+        o.add_module(Box::new(Farm::new("farm")));
+        o.add_module(Box::new(Battery::new("battery")));
+        o.energy_charge = 4;
+
+        let mut forecast = o.forecast(4);
+
+        // farm and battery drain the charge in two turns
+        forecast.cut_modules.sort();
+        assert_eq!(
+            vec![(String::from("battery"), 3), (String::from("farm"), 3)],
+            forecast.cut_modules
+        );
+        assert_eq!(None, forecast.food_runs_out);
+        assert!(!forecast.is_calm());
+        assert!(Outpost::new().forecast(3).is_calm());
+    }
+
+    #[test]
+    fn finish_turn_discards_overflow() {
+        let mut o = Outpost::new();
+        o.resources[Food] = 20;
+
+        let power = Box::new(PowerGenerator::new("power"));
+        let power_id = power.id().clone();
+        o.add_module(power);
+        let farm = Box::new(Farm::new("farm"));
+        let farm_id = farm.id().clone();
+        o.add_module(farm);
+        let a = CrewMember::new("a".to_string());
+        let a_id = a.id().clone();
+        o.add_crew_member(a);
+        o.assign_crew_member_to_module(&a_id, &power_id);
+        let b = CrewMember::new("b".to_string());
+        let b_id = b.id().clone();
+        o.add_crew_member(b);
+        o.assign_crew_member_to_module(&b_id, &farm_id);
+
+        let mut state = GameState::new(0);
+        o.finish_turn(&mut state);
+
+        // the 2 grown food don't fit, the crew eats 2
+        assert_eq!(18, o.resources[Food]);
+        assert_eq!(1, state.logs.len());
+    }
+
+    #[test]
+    fn unpowered_storage_keeps_its_stock() {
+        let mut o = Outpost::new();
+        let silo = Box::new(Silo::new("silo"));
+        let silo_id = silo.id().clone();
+        o.add_module(silo);
+        o.modules[&silo_id].unwrap_mut().set_energy_level(2);
+        o.resources[Food] = 45;
+
+        // a shortage cuts the silo back to its base load
+        o.modules[&silo_id].unwrap_mut().set_energy_level(0);
+        let mut state = GameState::new(0);
+        o.finish_turn(&mut state);
+
+        assert_eq!(45, o.resources[Food]);
+        assert!(state.logs.is_empty());
+    }
+
+    #[test]
+    fn storage_modules_increase_capacity() {
+        let mut o = Outpost::new();
+        assert_eq!(20, o.storage()[Food]);
+
+        let mut silo = Silo::new("silo");
+        silo.set_energy_level(0);
+        o.add_module(Box::new(silo));
+        let mut tank = WaterTank::new("tank");
+        tank.set_energy_level(0);
+        o.add_module(Box::new(tank));
 
-        o.finish_turn();
+        // unpowered storage modules still hold their base load
+        assert_eq!(30, o.storage()[Minerals]);
+        assert_eq!(30, o.storage()[Food]);
+        assert_eq!(30, o.storage()[Water]);
 
-        assert_eq!(6, o.resources.food);
-        assert_eq!(6, o.resources.water);
+        for m in o.modules.iter_mut() {
+            m.unwrap_mut().set_energy_level(2);
+        }
+        assert_eq!(50, o.storage()[Minerals]);
+        assert_eq!(50, o.storage()[Food]);
+        assert_eq!(50, o.storage()[Water]);
+    }
+
+    #[test]
+    fn surplus_energy_charges_batteries() {
+        let mut o = Outpost::new();
+        o.resources[Energy] = 4;
+        o.add_module(Box::new(Battery::new("battery")));
+
+        o.support_modules();
+        o.charge_batteries();
+
+        // the battery itself uses one energy
+        assert_eq!(3, o.energy_charge);
+
+        o.resources[Energy] = 4;
+        o.support_modules();
+        o.charge_batteries();
+
+        assert_eq!(5, o.energy_charge);
+    }
+
+    #[test]
+    fn crew_upkeep_comes_before_charging() {
+        let mut o = Outpost::new();
+        o.resources[Energy] = 4;
+        o.add_module(Box::new(Battery::new("battery")));
+        let mut a = CrewMember::new("a".to_string());
+        a.set_upkeep(Resources::energy(2));
+        o.add_crew_member(a);
+
+        o.support_modules();
+        o.support_crew();
+        o.charge_batteries();
+
+        // 4 energy, 1 for the battery and 2 for the crew
+        assert_eq!(1, o.energy_charge);
+    }
+
+    #[test]
+    fn cut_batteries_keep_their_charge() {
+        let mut o = Outpost::new();
+        let mut battery = Battery::new("battery");
+        battery.set_energy_level(0);
+        o.add_module(Box::new(battery));
+        o.energy_charge = 4;
+
+        o.support_modules();
+        o.charge_batteries();
+
+        assert_eq!(4, o.energy_charge);
+    }
+
+    #[test]
+    fn batteries_discharge_before_energy_is_cut() {
+        let mut o = Outpost::new();
+        o.resources[Energy] = 1;
+        o.energy_charge = 2;
+        o.add_module(Box::new(Battery::new("battery")));
+
+        let mut water = WaterExtractor::new("water_extractor1");
+        water.set_energy_level(3);
+        o.add_module(Box::new(water));
+
+        o.support_modules();
+
+        assert_eq!(3, consumption_of(&o, "water_extractor1"));
+        assert_eq!(0, o.energy_charge);
+    }
+
+    #[test]
+    fn start_mission_requires_every_resource() {
+        let mut o = Outpost::new();
+        o.resources = Resources::energy(100)
+            + Resources::minerals(2)
+            + Resources::food(10)
+            + Resources::water(1);
+        o.add_ship(Ship::new("shuttle", ShipClass::Shuttle));
+        o.select_next_ship_for_mission();
+
+        let a = CrewMember::new("a".to_string());
+        let a_id = a.id().clone();
+        o.add_crew_member(a);
+        o.prepare_crew_member_for_mission(&a_id).unwrap();
+        o.increment_prepare_for_turns();
+        o.increment_prepare_for_turns();
+
+        let mission = Mission::new("sub_sector".to_string(), MissionType::Mining(1, 2));
+        assert_eq!(
+            Some(MissionPreparationIssue::MissingSupplies(Resources::water(
+                1
+            ))),
+            o.start_mission(&mission, 1).err()
+        );
+        assert_eq!(
+            Resources::water(1),
+            o.describe_mission_preparation(&mission, 1, &Difficulty::Normal.config())
+                .shortfall
+        );
+
+        o.resources[Water] = 2;
+        let active_mission = o.start_mission(&mission, 1).unwrap();
+        assert_eq!(1, active_mission.distance);
+        assert_eq!(
+            Resources::food(2) + Resources::water(2),
+            active_mission.supplies
+        );
+        assert_eq!(0, o.resources[Minerals]);
+        assert_eq!(0, o.ships().len());
+    }
+
+    #[test]
+    fn ship_limits_mission_crew() {
+        let mut o = Outpost::new();
+        o.add_ship(Ship::new("shuttle", ShipClass::Shuttle));
+        o.select_next_ship_for_mission();
+        o.increment_prepare_for_turns();
+        for name in ["a", "b", "c"] {
+            let c = CrewMember::new(name.to_string());
+            let c_id = c.id().clone();
+            o.add_crew_member(c);
+            o.prepare_crew_member_for_mission(&c_id).unwrap();
+        }
+
+        assert_eq!(
+            vec![MissionPreparationIssue::TooManyCrew(2)],
+            o.mission_preparation_issues(0)
+        );
+    }
+
+    #[test]
+    fn build_ship_requires_docking_bay_and_minerals() {
+        let mut o = Outpost::new();
+        o.resources = Resources::minerals(15);
+        assert_eq!(
+            Err(ShipBuildIssue::NoFreeDockingBay),
+            o.build_ship(ShipClass::Shuttle)
+        );
+
+        o.add_module(Box::new(Hangar::new("hangar")));
+        assert_eq!(
+            Err(ShipBuildIssue::MissingMinerals(Resources::minerals(10))),
+            o.build_ship(ShipClass::Freighter)
+        );
+        assert_eq!(Ok(()), o.build_ship(ShipClass::Shuttle));
+        assert_eq!(Resources::minerals(5), o.resources);
+        assert_eq!(
+            Err(ShipBuildIssue::NoFreeDockingBay),
+            o.build_ship(ShipClass::Shuttle)
+        );
+    }
+
+    #[test]
+    fn mission_preparation_rejects_duplicates_and_empty_missions() {
+        let mut o = Outpost::new();
+        let mission = Mission::new("sub_sector".to_string(), MissionType::Mining(1, 2));
+        assert_eq!(
+            vec![
+                MissionPreparationIssue::NoCrew,
+                MissionPreparationIssue::NoTurns,
+                MissionPreparationIssue::NoShip
+            ],
+            o.mission_preparation_issues(0)
+        );
+
+        let a = CrewMember::new("a".to_string());
+        let a_id = a.id().clone();
+        o.add_crew_member(a);
+        o.prepare_crew_member_for_mission(&a_id).unwrap();
+        assert_eq!(
+            Err(MissionPreparationIssue::DuplicateCrewMember(
+                "a".to_string()
+            )),
+            o.prepare_crew_member_for_mission(&a_id)
+        );
+        assert_eq!(
+            Some(MissionPreparationIssue::NoTurns),
+            o.start_mission(&mission, 0).err()
+        );
+
+        o.remove_crew_member_from_mission(0);
+        o.increment_prepare_for_turns();
+        assert_eq!(
+            Some(MissionPreparationIssue::NoCrew),
+            o.start_mission(&mission, 0).err()
+        );
+    }
+
+    #[test]
+    fn move_crew_member_in_mission_stays_in_bounds() {
+        let mut o = Outpost::new();
+        let a = CrewMember::new("a".to_string());
+        let b = CrewMember::new("b".to_string());
+        let (a_id, b_id) = (a.id().clone(), b.id().clone());
+        o.add_crew_member(a);
+        o.add_crew_member(b);
+        o.prepare_crew_member_for_mission(&a_id).unwrap();
+        o.prepare_crew_member_for_mission(&b_id).unwrap();
+
+        assert_eq!(1, o.move_crew_member_in_mission(0, 1));
+        assert_eq!(
+            vec![b_id.clone(), a_id.clone()],
+            o.mission_preparation.crew_ids
+        );
+        assert_eq!(1, o.move_crew_member_in_mission(1, 1));
+        assert_eq!(0, o.move_crew_member_in_mission(0, -1));
+    }
+
+    #[test]
+    fn receive_mission_returns_crew_and_supplies() {
+        let mut o = Outpost::new();
+        o.resources = Resources::zero();
+        let mut state = GameState::new(0);
+        let mission = Mission::new("sub_sector".to_string(), MissionType::Mining(1, 2));
+        let mut a = CrewMember::new("a".to_string());
+        a.assign_to_mission(mission.id());
+
+        let mut active_mission = ActiveMission::new(mission.id(), Resources::food(2), vec![a]);
+        active_mission.resources = Resources::minerals(5);
+        active_mission.ship = Some(Ship::new("shuttle", ShipClass::Shuttle));
+        o.receive_mission(&mut state, active_mission);
+
+        assert_eq!(1, o.crew.len());
+        assert_eq!(1, o.ships().len());
+        assert!(o.crew.iter().all(|c| c.assigned_mission().is_none()));
+        assert_eq!(Resources::minerals(5) + Resources::food(2), o.resources);
+    }
+
+    #[test]
+    fn paying_off_raiders_costs_minerals() {
+        let mut o = Outpost::new();
+        let mut state = GameState::new(0);
+        o.pending_threat = Some(ThreatKind::Raiders);
+
+        o.resolve_threat(&mut state, 0);
+
+        assert!(o.pending_threat().is_none());
+        assert_eq!(0, o.resources[Minerals]);
+    }
+
+    #[test]
+    fn unknown_threat_options_keep_the_threat_pending() {
+        let mut o = Outpost::new();
+        let mut state = GameState::new(0);
+        o.pending_threat = Some(ThreatKind::Raiders);
+
+        o.resolve_threat(&mut state, 9);
+
+        assert_eq!(Some(&ThreatKind::Raiders), o.pending_threat());
+        assert_eq!(10, o.resources[Minerals]);
+        assert!(state.logs.is_empty());
+    }
+
+    #[test]
+    fn outbreak_injures_the_whole_crew() {
+        let mut o = Outpost::new();
+        let mut state = GameState::new(0);
+        let mut weak = CrewMember::new("weak".to_string());
+        weak.injure(4);
+        let weak_id = weak.id().clone();
+        o.add_crew_member(weak);
+        o.add_crew_member(CrewMember::new("strong".to_string()));
+        o.prepare_crew_member_for_mission(&weak_id).unwrap();
+
+        o.apply_threat_outcomes(&mut state, &vec![ThreatOutcome::Outbreak(1)]);
+
+        assert_eq!(1, o.crew.len());
+        assert_eq!(80, o.crew.iter().next().unwrap().health());
+        assert_eq!(1, o.cemetery.len());
+        assert!(o.mission_preparation.crew_ids.is_empty());
+    }
+
+    #[test]
+    fn shield_generators_add_up() {
+        let mut o = Outpost::new();
+        let mut shield = ShieldGenerator::new("shield");
+        shield.set_energy_level(2);
+        o.add_module(Box::new(shield));
+        o.add_module(Box::new(ShieldGenerator::new("shield")));
+
+        assert_eq!(75, o.shielding());
+    }
+
+    #[test]
+    fn guards_soften_raids() {
+        let mut o = Outpost::new();
+        let mut state = GameState::new(0);
+        let security = SecurityPost::new("security");
+        let security_id = security.id().clone();
+        o.add_module(Box::new(security));
+        let mut guard = CrewMember::new("guard".to_string());
+        guard.stats = Stats::military(20);
+        let guard_id = guard.id().clone();
+        o.add_crew_member(guard);
+        o.assign_crew_member_to_module(&guard_id, &security_id);
+        assert_eq!(23, o.defense());
+
+        o.pending_threat = Some(ThreatKind::Raiders);
+        o.resolve_threat(&mut state, 1);
+
+        assert_eq!(85, o.module_condition(&security_id));
+        assert_eq!(100, o.crew[&guard_id].health());
+    }
+
+    #[test]
+    fn damaged_modules_lose_output_and_broken_ones_stop() {
+        let mut o = Outpost::new();
+        let mut power = PowerGenerator::new("power");
+        power.set_energy_level(2);
+        let power_id = power.id().clone();
+        o.add_module(Box::new(power));
+        let full = o.production()[Energy];
+
+        o.modules[&power_id].damage(60);
+        assert_eq!(full * 75 / 100, o.production()[Energy]);
+
+        o.modules[&power_id].damage(40);
+        assert!(o.production().is_zero());
+        assert!(o.consumption().is_zero());
+    }
+
+    #[test]
+    fn repair_module_costs_minerals() {
+        let mut o = Outpost::new();
+        let farm = Farm::new("farm");
+        let farm_id = farm.id().clone();
+        o.add_module(Box::new(farm));
+
+        assert_eq!(Err(ModuleRepairIssue::NoDamage), o.repair_module(&farm_id));
+        o.modules[&farm_id].damage(35);
+        assert_eq!(Resources::minerals(4), o.repair_cost(&farm_id));
+        o.repair_module(&farm_id).unwrap();
+        assert_eq!(100, o.module_condition(&farm_id));
+        assert_eq!(6, o.resources[Minerals]);
+
+        o.modules[&farm_id].damage(100);
+        assert_eq!(
+            Err(ModuleRepairIssue::MissingMinerals(Resources::minerals(4))),
+            o.repair_module(&farm_id)
+        );
+    }
+
+    #[test]
+    fn modules_wear_and_engineers_repair_them() {
+        let mut o = Outpost::new();
+        let mut state = GameState::new(0);
+        let mut farm = Farm::new("farm");
+        farm.set_energy_level(1);
+        let farm_id = farm.id().clone();
+        o.add_module(Box::new(farm));
+        let mut water = WaterExtractor::new("water");
+        water.set_energy_level(2);
+        let water_id = water.id().clone();
+        o.add_module(Box::new(water));
+        o.modules[&farm_id].damage(50);
+
+        let mut engineer = CrewMember::new("engineer".to_string());
+        engineer.stats = Stats::engineering(20);
+        let engineer_id = engineer.id().clone();
+        o.add_crew_member(engineer);
+        o.assign_crew_member_to_module(&engineer_id, &farm_id);
+
+        o.maintain_modules(&mut state);
+
+        assert_eq!(52, o.module_condition(&farm_id));
+        assert_eq!(98, o.module_condition(&water_id));
     }
 
     #[test]
     fn finish_turn_cuts_energy_levels() {
         let mut o = Outpost::new();
 
-        let mut mine1 = Mine::new("mine1");
-        mine1.set_energy_level(3);
-        o.modules.push(Box::new(mine1));
+        let mut farm1 = Farm::new("farm1");
+        farm1.set_energy_level(3);
+        o.add_module(Box::new(farm1));
 
-        let mut mine2 = Mine::new("mine2");
-        mine2.set_energy_level(3);
-        o.modules.push(Box::new(mine2));
+        let mut water = WaterExtractor::new("water_extractor1");
+        water.set_energy_level(3);
+        o.add_module(Box::new(water));
+
+        o.finish_turn(&mut GameState::new(0));
+
+        // without a generator there is no energy to run either module
+        assert_eq!(0, consumption_of(&o, "farm1"));
+        assert_eq!(0, consumption_of(&o, "water_extractor1"));
+    }
+
+    #[test]
+    fn finish_turn_cuts_energy_levels_by_priority() {
+        let mut o = Outpost::new();
+        o.resources[Energy] = 3;
 
         let mut farm1 = Farm::new("farm1");
         farm1.set_energy_level(3);
-        o.modules.push(Box::new(farm1));
+        farm1.set_priority(ModulePriority::High);
+        o.add_module(Box::new(farm1));
 
         let mut water = WaterExtractor::new("water_extractor1");
         water.set_energy_level(3);
-        o.modules.push(Box::new(water));
-
-        o.finish_turn();
-
-        let assert_consumption = |expected: i32, name: &str| {
-            assert_eq!(
-                expected,
-                o.module(&name.to_string()).unwrap().consumption().energy,
-                "{} should have energy {}",
-                name,
-                expected
-            );
-        };
+        water.set_priority(ModulePriority::Low);
+        o.add_module(Box::new(water));
+
+        o.support_modules();
 
-        assert_consumption(0, "mine1");
-        assert_consumption(0, "mine2");
-        assert_consumption(3, "farm1");
-        assert_consumption(2, "water_extractor1");
+        assert_eq!(3, consumption_of(&o, "farm1"));
+        assert_eq!(0, consumption_of(&o, "water_extractor1"));
     }
 }
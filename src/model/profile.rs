@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+
+use super::run::{RunOutcome, RunSummary};
+
+/// what the player carries from one run to the next, kept apart from the save game
+#[derive(Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub runs: Vec<RunRecord>,
+    pub unlocks: Vec<Unlock>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RunRecord {
+    pub seed: String,
+    pub outcome: RunOutcome,
+    pub turns: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Unlock {
+    SupplyCache,
+    SpareGenerator,
+    Veterans,
+}
+
+impl Unlock {
+    pub const ALL: [Unlock; 3] = [
+        Unlock::SupplyCache,
+        Unlock::SpareGenerator,
+        Unlock::Veterans,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        use Unlock::*;
+        match self {
+            SupplyCache => "supply cache",
+            SpareGenerator => "spare generator",
+            Veterans => "veterans",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        use Unlock::*;
+        match self {
+            SupplyCache => "start with 10 more minerals, food and water",
+            SpareGenerator => "start with a second power generator",
+            Veterans => "one of the starting crew is a veteran soldier",
+        }
+    }
+
+    pub fn requirement(&self) -> &'static str {
+        use Unlock::*;
+        match self {
+            SupplyCache => "finish 3 runs",
+            SpareGenerator => "survive 30 turns",
+            Veterans => "win a run",
+        }
+    }
+
+    fn is_earned(&self, profile: &Profile) -> bool {
+        use Unlock::*;
+        match self {
+            SupplyCache => profile.runs.len() >= 3,
+            SpareGenerator => profile.runs.iter().any(|r| r.turns >= 30),
+            Veterans => profile.wins() > 0,
+        }
+    }
+}
+
+impl Profile {
+    pub fn has(&self, unlock: Unlock) -> bool {
+        self.unlocks.contains(&unlock)
+    }
+
+    pub fn wins(&self) -> usize {
+        self.runs.iter().filter(|r| r.outcome.is_victory()).count()
+    }
+
+    pub fn best_turns(&self) -> u32 {
+        self.runs.iter().map(|r| r.turns).max().unwrap_or(0)
+    }
+
+    /// records a finished run and returns what it unlocked
+    pub fn record_run(&mut self, seed: &str, summary: &RunSummary) -> Vec<Unlock> {
+        self.runs.push(RunRecord {
+            seed: seed.to_string(),
+            outcome: summary.outcome.clone(),
+            turns: summary.turns,
+        });
+        let unlocked: Vec<Unlock> = Unlock::ALL
+            .iter()
+            .filter(|u| !self.has(**u) && u.is_earned(self))
+            .cloned()
+            .collect();
+        self.unlocks.extend(unlocked.iter());
+        unlocked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Profile, Unlock};
+    use crate::model::run::{RunOutcome, RunSummary};
+
+    fn summary(outcome: RunOutcome, turns: u32) -> RunSummary {
+        RunSummary {
+            outcome,
+            turns,
+            crew: 0,
+            casualties: 0,
+            missions: 0,
+            blueprints: 0,
+            visited_sub_sectors: 0,
+            sub_sectors: 0,
+        }
+    }
+
+    #[test]
+    fn finished_runs_unlock_once() {
+        let mut profile = Profile::default();
+
+        assert!(profile
+            .record_run("seed", &summary(RunOutcome::CrewLost, 10))
+            .is_empty());
+        assert_eq!(
+            vec![Unlock::SpareGenerator],
+            profile.record_run("seed", &summary(RunOutcome::Collapse, 40))
+        );
+        assert_eq!(
+            vec![Unlock::SupplyCache, Unlock::Veterans],
+            profile.record_run("seed", &summary(RunOutcome::SectorCharted, 20))
+        );
+        assert!(profile
+            .record_run("seed", &summary(RunOutcome::FinalResearch, 50))
+            .is_empty());
+
+        assert_eq!(2, profile.wins());
+        assert_eq!(50, profile.best_turns());
+        assert_eq!(3, profile.unlocks.len());
+    }
+}
@@ -1,127 +1,235 @@
-use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+use std::collections::BTreeMap;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Sub, SubAssign};
 
+use catppuccin::{Colour, Flavour};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize)]
-pub struct Resources {
-    pub energy: i32,
-    pub living_space: i32,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceType {
+    Energy,
+    LivingSpace,
+    Minerals,
+    Food,
+    Water,
+}
 
-    pub minerals: i32,
-    pub food: i32,
-    pub water: i32,
+/// Flows are produced and consumed within a single turn, stocks are kept between turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    Flow,
+    Stock,
+}
+
+pub struct ResourceMetadata {
+    pub name: &'static str,
+    pub glyph: char,
+    pub colour: fn(Flavour) -> Colour,
+    pub kind: ResourceKind,
+    pub base_storage: i32,
+}
+
+impl ResourceType {
+    pub const ALL: [ResourceType; 5] = [
+        ResourceType::Energy,
+        ResourceType::LivingSpace,
+        ResourceType::Minerals,
+        ResourceType::Food,
+        ResourceType::Water,
+    ];
+
+    pub fn metadata(&self) -> ResourceMetadata {
+        use ResourceKind::*;
+        use ResourceType::*;
+        match self {
+            Energy => ResourceMetadata {
+                name: "energy",
+                glyph: 'e',
+                colour: Flavour::yellow,
+                kind: Flow,
+                base_storage: 0,
+            },
+            LivingSpace => ResourceMetadata {
+                name: "living space",
+                glyph: 'l',
+                colour: Flavour::peach,
+                kind: Flow,
+                base_storage: 0,
+            },
+            Minerals => ResourceMetadata {
+                name: "minerals",
+                glyph: 'm',
+                colour: Flavour::sapphire,
+                kind: Stock,
+                base_storage: 20,
+            },
+            Food => ResourceMetadata {
+                name: "food",
+                glyph: 'f',
+                colour: Flavour::green,
+                kind: Stock,
+                base_storage: 20,
+            },
+            Water => ResourceMetadata {
+                name: "water",
+                glyph: 'w',
+                colour: Flavour::blue,
+                kind: Stock,
+                base_storage: 20,
+            },
+        }
+    }
+
+    pub fn is_stock(&self) -> bool {
+        self.metadata().kind == ResourceKind::Stock
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Resources {
+    values: BTreeMap<ResourceType, i32>,
 }
 
 impl Resources {
     pub fn zero() -> Resources {
         Resources {
-            energy: 0,
-            living_space: 0,
-            minerals: 0,
-            food: 0,
-            water: 0,
+            values: BTreeMap::new(),
         }
     }
 
+    pub fn of(resource_type: ResourceType, amount: i32) -> Resources {
+        let mut result = Resources::zero();
+        result[resource_type] = amount;
+        result
+    }
+
     pub fn energy(energy: i32) -> Resources {
-        Resources {
-            energy,
-            living_space: 0,
-            minerals: 0,
-            food: 0,
-            water: 0,
-        }
+        Resources::of(ResourceType::Energy, energy)
     }
 
     pub fn living_space(living_space: i32) -> Resources {
-        Resources {
-            energy: 0,
-            living_space,
-            minerals: 0,
-            food: 0,
-            water: 0,
-        }
+        Resources::of(ResourceType::LivingSpace, living_space)
     }
 
     pub fn minerals(minerals: i32) -> Resources {
-        Resources {
-            energy: 0,
-            living_space: 0,
-            minerals,
-            food: 0,
-            water: 0,
-        }
+        Resources::of(ResourceType::Minerals, minerals)
     }
 
     pub fn food(food: i32) -> Resources {
-        Resources {
-            energy: 0,
-            living_space: 0,
-            minerals: 0,
-            food,
-            water: 0,
-        }
+        Resources::of(ResourceType::Food, food)
     }
 
     pub fn water(water: i32) -> Resources {
-        Resources {
-            energy: 0,
-            living_space: 0,
-            minerals: 0,
-            food: 0,
-            water,
+        Resources::of(ResourceType::Water, water)
+    }
+
+    pub fn base_storage() -> Resources {
+        Resources::from_fn(|t| t.metadata().base_storage)
+    }
+
+    pub fn from_fn<F>(mut f: F) -> Resources
+    where
+        F: FnMut(ResourceType) -> i32,
+    {
+        let mut result = Resources::zero();
+        for t in ResourceType::ALL {
+            result[t] = f(t);
         }
+        result
+    }
+
+    pub fn map<F>(&self, mut f: F) -> Resources
+    where
+        F: FnMut(ResourceType, i32) -> i32,
+    {
+        Resources::from_fn(|t| f(t, self[t]))
+    }
+
+    pub fn zip<F>(&self, other: &Resources, mut f: F) -> Resources
+    where
+        F: FnMut(ResourceType, i32, i32) -> i32,
+    {
+        Resources::from_fn(|t| f(t, self[t], other[t]))
+    }
+
+    /// keeps only the resources that are kept between turns
+    pub fn stocks(&self) -> Resources {
+        self.map(|t, v| if t.is_stock() { v } else { 0 })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ResourceType, i32)> + '_ {
+        ResourceType::ALL.into_iter().map(|t| (t, self[t]))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.iter().all(|(_, v)| v == 0)
+    }
+
+    /// true if there is at least as much of every resource as `other` requires
+    pub fn covers(&self, other: &Resources) -> bool {
+        other.iter().all(|(t, v)| self[t] >= v)
+    }
+
+    /// the amount of each resource that is missing to cover `other`
+    pub fn shortfall(&self, other: &Resources) -> Resources {
+        other.zip(self, |_, needed, available| {
+            std::cmp::max(needed - available, 0)
+        })
     }
 }
 
+impl Index<ResourceType> for Resources {
+    type Output = i32;
+
+    fn index(&self, resource_type: ResourceType) -> &Self::Output {
+        self.values.get(&resource_type).unwrap_or(&0)
+    }
+}
+
+impl IndexMut<ResourceType> for Resources {
+    fn index_mut(&mut self, resource_type: ResourceType) -> &mut Self::Output {
+        self.values.entry(resource_type).or_insert(0)
+    }
+}
+
+impl PartialEq for Resources {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+impl Eq for Resources {}
+
 impl Add for Resources {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        Self {
-            energy: self.energy + other.energy,
-            living_space: self.living_space + other.living_space,
-            minerals: self.minerals + other.minerals,
-            food: self.food + other.food,
-            water: self.water + other.water,
-        }
+        self.zip(&other, |_, a, b| a + b)
     }
 }
 impl Sub for Resources {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        Self {
-            energy: self.energy - other.energy,
-            living_space: self.living_space - other.living_space,
-            minerals: self.minerals - other.minerals,
-            food: self.food - other.food,
-            water: self.water - other.water,
-        }
+        self.zip(&other, |_, a, b| a - b)
     }
 }
 
 impl AddAssign for Resources {
     fn add_assign(&mut self, other: Self) {
-        *self = Self {
-            energy: other.energy,
-            living_space: other.living_space,
-            minerals: self.minerals + other.minerals,
-            food: self.food + other.food,
-            water: self.water + other.water,
-        };
+        *self = self.zip(&other, |t, a, b| if t.is_stock() { a + b } else { b });
     }
 }
 
 impl SubAssign for Resources {
     fn sub_assign(&mut self, other: Self) {
-        *self = Self {
-            energy: self.energy,
-            living_space: self.living_space,
-            minerals: std::cmp::max(self.minerals - other.minerals, 0),
-            food: std::cmp::max(self.food - other.food, 0),
-            water: std::cmp::max(self.water - other.water, 0),
-        };
+        *self = self.zip(&other, |t, a, b| {
+            if t.is_stock() {
+                std::cmp::max(a - b, 0)
+            } else {
+                a
+            }
+        });
     }
 }
 
@@ -129,12 +237,69 @@ impl Mul<i32> for Resources {
     type Output = Self;
 
     fn mul(self, rhs: i32) -> Self {
-        Self {
-            energy: rhs * self.energy,
-            living_space: rhs * self.living_space,
-            minerals: rhs * self.minerals,
-            food: rhs * self.food,
-            water: rhs * self.water,
-        }
+        self.map(|_, v| rhs * v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ResourceType, Resources};
+
+    #[test]
+    fn deserialize_legacy_resources() {
+        let legacy = r#"{"energy":1,"living_space":2,"minerals":3,"food":4,"water":5}"#;
+        let resources: Resources = serde_json::from_str(legacy).unwrap();
+
+        assert_eq!(1, resources[ResourceType::Energy]);
+        assert_eq!(2, resources[ResourceType::LivingSpace]);
+        assert_eq!(3, resources[ResourceType::Minerals]);
+        assert_eq!(4, resources[ResourceType::Food]);
+        assert_eq!(5, resources[ResourceType::Water]);
+    }
+
+    #[test]
+    fn missing_resources_are_zero() {
+        let resources: Resources = serde_json::from_str(r#"{"food":4}"#).unwrap();
+
+        assert_eq!(Resources::food(4), resources);
+        assert_eq!(0, resources[ResourceType::Water]);
+    }
+
+    #[test]
+    fn covers_every_resource() {
+        let available = Resources::energy(5) + Resources::water(1);
+
+        assert!(available.covers(&Resources::energy(5)));
+        assert!(available.covers(&Resources::zero()));
+        assert!(!available.covers(&Resources::water(2)));
+        assert!(!available.covers(&(Resources::energy(1) + Resources::water(2))));
+    }
+
+    #[test]
+    fn shortfall_lists_missing_resources() {
+        let available = Resources::energy(5) + Resources::water(1);
+        let needed = Resources::energy(2) + Resources::water(3) + Resources::food(1);
+
+        assert_eq!(
+            Resources::water(2) + Resources::food(1),
+            available.shortfall(&needed)
+        );
+        assert!(available.shortfall(&Resources::energy(5)).is_zero());
+    }
+
+    #[test]
+    fn add_assign_replaces_flows_and_adds_stocks() {
+        let mut resources = Resources::energy(3) + Resources::food(2);
+        resources += Resources::energy(1) + Resources::food(1);
+
+        assert_eq!(Resources::energy(1) + Resources::food(3), resources);
+    }
+
+    #[test]
+    fn sub_assign_keeps_flows_and_saturates_stocks() {
+        let mut resources = Resources::energy(3) + Resources::food(2);
+        resources -= Resources::energy(1) + Resources::food(5);
+
+        assert_eq!(Resources::energy(3), resources);
     }
 }
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+/// turns the outpost may go without generating energy or filling any stock before the run is lost
+pub const COLLAPSE_TURNS: u32 = 5;
+/// blueprints needed to finish the final research when a scenario sets no objectives
+pub const FINAL_RESEARCH_BLUEPRINTS: u32 = 10;
+
+/// a way to win the run, each scenario declares its own
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Objective {
+    /// piece together the given number of blueprints
+    FinalResearch(u32),
+    /// visit every sub sector
+    SectorCharted,
+    /// hold out for the given number of turns
+    Survive(u32),
+}
+
+impl Objective {
+    pub fn standard() -> Vec<Objective> {
+        vec![
+            Objective::FinalResearch(FINAL_RESEARCH_BLUEPRINTS),
+            Objective::SectorCharted,
+        ]
+    }
+
+    pub fn outcome(&self) -> RunOutcome {
+        use Objective::*;
+        match self {
+            FinalResearch(_) => RunOutcome::FinalResearch,
+            SectorCharted => RunOutcome::SectorCharted,
+            Survive(_) => RunOutcome::Survived,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        use Objective::*;
+        match self {
+            FinalResearch(blueprints) => format!("recover {} blueprints", blueprints),
+            SectorCharted => String::from("visit every sub sector"),
+            Survive(turns) => format!("hold out for {} turns", turns),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunOutcome {
+    CrewLost,
+    Collapse,
+    FinalResearch,
+    SectorCharted,
+    Survived,
+}
+
+impl RunOutcome {
+    pub fn is_victory(&self) -> bool {
+        matches!(
+            self,
+            RunOutcome::FinalResearch | RunOutcome::SectorCharted | RunOutcome::Survived
+        )
+    }
+
+    pub fn description(&self) -> &'static str {
+        use RunOutcome::*;
+        match self {
+            CrewLost => "The last crew member died, the outpost lies silent.",
+            Collapse => "Without power or production the outpost collapsed and was abandoned.",
+            FinalResearch => "The blueprints were pieced together into a jump drive.",
+            SectorCharted => "Every sub sector has been visited, the sector is charted.",
+            Survived => "The outpost held out until the relief ship arrived.",
+        }
+    }
+}
+
+pub struct RunSummary {
+    pub outcome: RunOutcome,
+    pub turns: u32,
+    pub crew: usize,
+    pub casualties: usize,
+    pub missions: usize,
+    pub blueprints: u32,
+    pub visited_sub_sectors: usize,
+    pub sub_sectors: usize,
+}
@@ -0,0 +1,344 @@
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::modules::{
+    battery::Battery, farm::Farm, hangar::Hangar, living_quarters::LivingQuarters,
+    power_generator::PowerGenerator, security_post::SecurityPost,
+    shield_generator::ShieldGenerator, silo::Silo, water_extractor::WaterExtractor,
+    water_tank::WaterTank, Module,
+};
+use super::resources::Resources;
+use super::run::Objective;
+use super::sector::{MissionType, SectorType};
+use super::ship::ShipClass;
+use super::stats::Stats;
+
+pub const SCENARIO_DIR: &str = "./scenarios";
+
+/// the starting conditions of a run as declared in a scenario file
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub resources: Resources,
+    pub modules: Vec<ScenarioModule>,
+    pub crew: Vec<ScenarioCrewMember>,
+    #[serde(default)]
+    pub ships: Vec<ScenarioShip>,
+    /// the outpost sits in the sub sector at (0, 0)
+    pub sector: Vec<ScenarioSubSector>,
+    #[serde(default)]
+    pub missions: Vec<ScenarioMission>,
+    #[serde(default = "Objective::standard")]
+    pub objectives: Vec<Objective>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum ModuleKind {
+    PowerGenerator,
+    LivingQuarters,
+    WaterExtractor,
+    Farm,
+    Silo,
+    WaterTank,
+    Battery,
+    Hangar,
+    ShieldGenerator,
+    SecurityPost,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScenarioModule {
+    pub kind: ModuleKind,
+    pub name: String,
+    /// the module's own default if missing
+    #[serde(default)]
+    pub energy_level: Option<i32>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScenarioCrewMember {
+    pub name: String,
+    #[serde(default)]
+    pub stats: Stats,
+    /// the name of the module the crew member works in
+    #[serde(default)]
+    pub module: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScenarioShip {
+    pub name: String,
+    pub class: ShipClass,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScenarioSubSector {
+    pub x: i32,
+    pub y: i32,
+    pub sector_type: SectorType,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScenarioMission {
+    pub x: i32,
+    pub y: i32,
+    pub mission_type: MissionType,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ScenarioIssue {
+    Unreadable(String),
+    Invalid(String),
+    NoCrew,
+    NoOutpostSubSector,
+    UnknownModule(String),
+    UnknownSubSector(i32, i32),
+    DuplicateSubSector(i32, i32),
+    DuplicateModule(String),
+    InvalidMiningYield(i32, i32),
+}
+
+impl fmt::Display for ScenarioIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ScenarioIssue::*;
+        match self {
+            Unreadable(path) => write!(f, "{} could not be read", path),
+            Invalid(message) => write!(f, "invalid scenario: {}", message),
+            NoCrew => write!(f, "the scenario has no crew"),
+            NoOutpostSubSector => write!(f, "the sector has no sub sector at (0, 0)"),
+            UnknownModule(name) => write!(f, "there is no module named {}", name),
+            UnknownSubSector(x, y) => write!(f, "there is no sub sector at ({}, {})", x, y),
+            DuplicateSubSector(x, y) => {
+                write!(f, "the sub sector at ({}, {}) is declared twice", x, y)
+            }
+            DuplicateModule(name) => write!(f, "there is more than one module named {}", name),
+            InvalidMiningYield(x, y) => write!(
+                f,
+                "the mining mission at ({}, {}) must yield a minimum below its maximum",
+                x, y
+            ),
+        }
+    }
+}
+
+impl ModuleKind {
+    pub fn build(&self, name: &str) -> Box<dyn Module> {
+        match self {
+            ModuleKind::PowerGenerator => Box::new(PowerGenerator::new(name)),
+            ModuleKind::LivingQuarters => Box::new(LivingQuarters::new(name)),
+            ModuleKind::WaterExtractor => Box::new(WaterExtractor::new(name)),
+            ModuleKind::Farm => Box::new(Farm::new(name)),
+            ModuleKind::Silo => Box::new(Silo::new(name)),
+            ModuleKind::WaterTank => Box::new(WaterTank::new(name)),
+            ModuleKind::Battery => Box::new(Battery::new(name)),
+            ModuleKind::Hangar => Box::new(Hangar::new(name)),
+            ModuleKind::ShieldGenerator => Box::new(ShieldGenerator::new(name)),
+            ModuleKind::SecurityPost => Box::new(SecurityPost::new(name)),
+        }
+    }
+}
+
+impl ScenarioModule {
+    pub fn build(&self) -> Box<dyn Module> {
+        let mut module = self.kind.build(&self.name);
+        if let Some(energy_level) = self.energy_level {
+            module.set_energy_level(energy_level);
+        }
+        module
+    }
+}
+
+impl Scenario {
+    /// the scenario that ships with the game, used when no scenario files are found
+    pub fn standard() -> Scenario {
+        Scenario::parse(include_str!("../../scenarios/standard.json")).unwrap()
+    }
+
+    pub fn parse(data: &str) -> Result<Scenario, ScenarioIssue> {
+        let scenario: Scenario =
+            serde_json::from_str(data).map_err(|e| ScenarioIssue::Invalid(e.to_string()))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub fn load(path: &Path) -> Result<Scenario, ScenarioIssue> {
+        let data = std::fs::read_to_string(path)
+            .map_err(|_| ScenarioIssue::Unreadable(path.display().to_string()))?;
+        Scenario::parse(&data)
+    }
+
+    /// every valid scenario in the directory sorted by name, along with the
+    /// files that were skipped and why
+    pub fn load_all(dir: &Path) -> (Vec<Scenario>, Vec<(String, ScenarioIssue)>) {
+        let mut scenarios = vec![];
+        let mut issues = vec![];
+        let paths = std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().map(|e| e == "json").unwrap_or(false))
+                    .collect()
+            })
+            .unwrap_or_else(|_| vec![]);
+        for path in paths {
+            match Scenario::load(&path) {
+                Ok(scenario) => scenarios.push(scenario),
+                Err(issue) => issues.push((path.display().to_string(), issue)),
+            }
+        }
+        if scenarios.is_empty() {
+            scenarios.push(Scenario::standard());
+        }
+        scenarios.sort_by(|a, b| a.name.cmp(&b.name));
+        issues.sort_by(|a, b| a.0.cmp(&b.0));
+        (scenarios, issues)
+    }
+
+    fn validate(&self) -> Result<(), ScenarioIssue> {
+        if self.crew.is_empty() {
+            return Err(ScenarioIssue::NoCrew);
+        }
+        let has_sub_sector = |x: i32, y: i32| self.sector.iter().any(|s| s.x == x && s.y == y);
+        if !has_sub_sector(0, 0) {
+            return Err(ScenarioIssue::NoOutpostSubSector);
+        }
+        for (i, s) in self.sector.iter().enumerate() {
+            if self.sector[..i].iter().any(|o| o.x == s.x && o.y == s.y) {
+                return Err(ScenarioIssue::DuplicateSubSector(s.x, s.y));
+            }
+        }
+        if let Some(m) = self.missions.iter().find(|m| !has_sub_sector(m.x, m.y)) {
+            return Err(ScenarioIssue::UnknownSubSector(m.x, m.y));
+        }
+        // the yield is rolled from min..max which needs a non empty range
+        for m in self.missions.iter() {
+            if let MissionType::Mining(min, max) = m.mission_type {
+                if min >= max {
+                    return Err(ScenarioIssue::InvalidMiningYield(m.x, m.y));
+                }
+            }
+        }
+        for (i, m) in self.modules.iter().enumerate() {
+            if self.modules[..i].iter().any(|o| o.name == m.name) {
+                return Err(ScenarioIssue::DuplicateModule(m.name.clone()));
+            }
+        }
+        for module_name in self.crew.iter().filter_map(|c| c.module.as_ref()) {
+            if !self.modules.iter().any(|m| &m.name == module_name) {
+                return Err(ScenarioIssue::UnknownModule(module_name.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scenario, ScenarioIssue};
+    use crate::model::profile::Profile;
+    use crate::model::{Game, GameSetup};
+    use std::path::Path;
+
+    const MINIMAL: &str = r#"{
+        "name": "minimal",
+        "description": "",
+        "modules": [{ "kind": "PowerGenerator", "name": "power" }],
+        "crew": [{ "name": "Ada", "module": "power" }],
+        "sector": [{ "x": 0, "y": 0, "sector_type": "EmptySpace" }]
+    }"#;
+
+    #[test]
+    fn every_scenario_file_starts_a_game() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios");
+        let files = std::fs::read_dir(&dir).unwrap().count();
+        let (scenarios, issues) = Scenario::load_all(&dir);
+        assert_eq!(files, scenarios.len());
+        assert!(issues.is_empty());
+
+        for scenario in scenarios {
+            let crew = scenario.crew.len();
+            let sub_sectors = scenario.sector.len();
+            let mut setup = GameSetup::random();
+            setup.scenario = scenario;
+            let game = Game::new(&setup, &Profile::default());
+
+            assert_eq!(crew, game.outpost.crew_len());
+            assert_eq!(sub_sectors, game.sector.sub_sectors_len());
+        }
+    }
+
+    #[test]
+    fn broken_scenarios_are_rejected() {
+        assert!(Scenario::parse(MINIMAL).is_ok());
+        assert!(matches!(
+            Scenario::parse("{}"),
+            Err(ScenarioIssue::Invalid(_))
+        ));
+        assert_eq!(
+            Some(ScenarioIssue::NoOutpostSubSector),
+            Scenario::parse(&MINIMAL.replace(r#""x": 0"#, r#""x": 1"#)).err()
+        );
+        assert_eq!(
+            Some(ScenarioIssue::UnknownModule(String::from("farm"))),
+            Scenario::parse(&MINIMAL.replace(r#""module": "power""#, r#""module": "farm""#)).err()
+        );
+        assert_eq!(
+            Some(ScenarioIssue::NoCrew),
+            Scenario::parse(&MINIMAL.replace(r#"{ "name": "Ada", "module": "power" }"#, "")).err()
+        );
+    }
+
+    #[test]
+    fn skipped_scenario_files_are_reported() {
+        let dir = std::env::temp_dir().join(format!("scenarios-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("minimal.json"), MINIMAL).unwrap();
+        std::fs::write(dir.join("broken.json"), "{}").unwrap();
+
+        let (scenarios, issues) = Scenario::load_all(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(1, scenarios.len());
+        assert_eq!("minimal", scenarios[0].name);
+        assert_eq!(1, issues.len());
+        assert!(issues[0].0.ends_with("broken.json"));
+        assert!(matches!(issues[0].1, ScenarioIssue::Invalid(_)));
+    }
+
+    #[test]
+    fn ambiguous_scenarios_are_rejected() {
+        let sector = r#"{ "x": 0, "y": 0, "sector_type": "EmptySpace" }"#;
+        assert_eq!(
+            Some(ScenarioIssue::DuplicateSubSector(0, 0)),
+            Scenario::parse(&MINIMAL.replace(sector, &format!("{}, {}", sector, sector))).err()
+        );
+        let module = r#"{ "kind": "PowerGenerator", "name": "power" }"#;
+        assert_eq!(
+            Some(ScenarioIssue::DuplicateModule(String::from("power"))),
+            Scenario::parse(&MINIMAL.replace(module, &format!("{}, {}", module, module))).err()
+        );
+        let mining = |min: u16, max: u16| {
+            MINIMAL.replace(
+                r#""sector": ["#,
+                &format!(
+                    r#""missions": [{{ "x": 0, "y": 0, "mission_type": {{ "Mining": [{}, {}] }} }}], "sector": ["#,
+                    min, max
+                ),
+            )
+        };
+        assert!(Scenario::parse(&mining(3, 5)).is_ok());
+        assert_eq!(
+            Some(ScenarioIssue::InvalidMiningYield(0, 0)),
+            Scenario::parse(&mining(5, 5)).err()
+        );
+        assert_eq!(
+            Some(ScenarioIssue::InvalidMiningYield(0, 0)),
+            Scenario::parse(&mining(6, 5)).err()
+        );
+    }
+}
@@ -3,13 +3,19 @@ use rand::Rng;
 use serde::de::Error as _;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
-use super::crew::CrewMember;
+use super::crew::{skill_chance, team_stats, team_upkeep, CrewMember};
+use super::difficulty::DifficultyConfig;
 use super::game_state::GameState;
-use super::resources::Resources;
+use super::mission_events::{MissionEventKind, MissionEventOutcome};
+use super::mission_report::{CrewHealthChange, MissionReport};
+use super::resources::{ResourceType, ResourceType::Minerals, Resources};
+use super::ship::Ship;
+use super::stats::Stats;
 use super::{AxialHexCoordinates, Entity, Storage};
 
-#[derive(Hash, PartialEq, Eq)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Coordinates {
     pub x: i32,
     pub y: i32,
@@ -52,6 +58,10 @@ impl Serialize for Coordinates {
     }
 }
 
+const MISSION_SPAWN_CHANCE: f64 = 0.1;
+const BLUEPRINT_CHANCE: f64 = 0.15;
+const MISSION_DURATION: std::ops::Range<u32> = 6..16;
+
 #[derive(Serialize, Deserialize)]
 pub struct Sector {
     sub_sectors: Storage<SubSector>,
@@ -81,10 +91,147 @@ impl Sector {
     pub fn finish_turn(&mut self, state: &mut GameState) {
         self.active_mission.as_mut().map(|a| {
             let mission = &self.missions[&a.mission_id];
-            a.finish_turn(state, mission);
+            let sub_sector = &self.sub_sectors[&mission.sub_sector_id];
+            a.finish_turn(state, mission, &sub_sector.sector_type);
+        });
+
+        let arrived_at = match &self.active_mission {
+            Some(a) if a.has_arrived() => {
+                let mission = &self.missions[&a.mission_id];
+                Some((
+                    mission.sub_sector_id.clone(),
+                    mission.mission_type.clone(),
+                    a.crew_stats(),
+                ))
+            }
+            _ => None,
+        };
+        if let Some((sub_sector_id, mission_type, team)) = arrived_at {
+            self.arrive_at(state, &sub_sector_id, &mission_type, &team);
+        }
+
+        self.remove_expired_missions(state);
+        self.spawn_missions(state);
+    }
+
+    fn remove_expired_missions(&mut self, state: &mut GameState) {
+        let active_mission_id = self.active_mission.as_ref().map(|a| a.mission_id.clone());
+        let expired = self
+            .missions
+            .iter()
+            .filter(|m| m.is_expired(state.current_turn))
+            .filter(|m| Some(&m.id) != active_mission_id.as_ref())
+            .count();
+        if expired > 0 {
+            state.log(&format!("{} missions expired", expired));
+        }
+        self.missions.retain(|m| {
+            !m.is_expired(state.current_turn) || Some(&m.id) == active_mission_id.as_ref()
         });
     }
 
+    /// every known sub sector without a mission may post a new one, the outpost's own excluded
+    fn spawn_missions(&mut self, state: &mut GameState) {
+        let mut candidates: Vec<Coordinates> = self
+            .sub_sectors_map
+            .iter()
+            .filter(|(c, _)| c.hex_length() > 0)
+            .filter(|(_, id)| self.sub_sectors[*id].visibility != Visibility::Undiscovered)
+            .filter(|(_, id)| !self.missions.iter().any(|m| m.sub_sector_id == **id))
+            .map(|(c, _)| *c)
+            .collect();
+        // the map iterates in random order, sort to keep the seeded rng reproducible
+        candidates.sort();
+
+        for coordinates in candidates {
+            if !state.rng.gen_bool(MISSION_SPAWN_CHANCE) {
+                continue;
+            }
+            let sub_sector = &self.sub_sectors[&self.sub_sectors_map[&coordinates]];
+            let mission_type = sub_sector.sector_type.random_mission(state);
+            let expires_at = state.current_turn + state.rng.gen_range(MISSION_DURATION);
+            state.log(&format!(
+                "new {} posted at ({}, {})",
+                mission_type, coordinates.x, coordinates.y
+            ));
+            self.add_mission(coordinates.x, coordinates.y, mission_type, Some(expires_at));
+        }
+    }
+
+    /// all missions on known sub sectors, the most urgent first
+    pub fn mission_board(&self) -> Vec<(Coordinates, &Mission)> {
+        let mut board: Vec<(Coordinates, &Mission)> = self
+            .sub_sectors_map
+            .iter()
+            .filter(|(_, id)| self.sub_sectors[*id].visibility != Visibility::Undiscovered)
+            .flat_map(|(c, id)| {
+                self.missions
+                    .iter()
+                    .filter(move |m| m.sub_sector_id == *id)
+                    .map(move |m| (*c, m))
+            })
+            .collect();
+        board.sort_by_key(|(c, m)| (m.expires_at.unwrap_or(u32::MAX), *c));
+        board
+    }
+
+    /// marks the sub sector as visited and reveals its neighbours
+    pub fn visit(&mut self, x: i32, y: i32) -> Vec<Coordinates> {
+        let center = Coordinates::new(x, y);
+        self.sub_sectors[&self.sub_sectors_map[&center]].visibility = Visibility::Visited;
+
+        let mut discovered = vec![];
+        for (coordinates, id) in &self.sub_sectors_map {
+            let sub_sector = &mut self.sub_sectors[id];
+            if coordinates.hex_distance_to(center) == 1
+                && sub_sector.visibility == Visibility::Undiscovered
+            {
+                sub_sector.visibility = Visibility::Discovered;
+                discovered.push(*coordinates);
+            }
+        }
+        // the map iterates in random order, sort to keep the seeded rng reproducible
+        discovered.sort();
+        discovered
+    }
+
+    /// only explorers look around, every other crew just visits its destination
+    fn arrive_at(
+        &mut self,
+        state: &mut GameState,
+        sub_sector_id: &String,
+        mission_type: &MissionType,
+        team: &Stats,
+    ) {
+        let center = self
+            .sub_sectors_map
+            .iter()
+            .find(|(_, id)| *id == sub_sector_id)
+            .map(|(c, _)| *c)
+            .unwrap();
+        let sub_sector = &mut self.sub_sectors[sub_sector_id];
+        if sub_sector.visibility == Visibility::Visited {
+            return;
+        }
+        if !matches!(mission_type, MissionType::Exploration) {
+            sub_sector.visibility = Visibility::Visited;
+            return;
+        }
+
+        for coordinates in self.visit(center.x, center.y) {
+            state.log(&format!(
+                "discovered sub sector ({}, {})",
+                coordinates.x, coordinates.y
+            ));
+            if state.rng.gen_bool(skill_chance(team.astrophysics)) {
+                let sub_sector = &self.sub_sectors[&self.sub_sectors_map[&coordinates]];
+                let mission_type = sub_sector.sector_type.random_mission(state);
+                let expires_at = state.current_turn + state.rng.gen_range(MISSION_DURATION);
+                self.add_mission(coordinates.x, coordinates.y, mission_type, Some(expires_at));
+            }
+        }
+    }
+
     pub fn bounds_at_y(&self, y: i32) -> (i32, i32) {
         let mut result = (0, 0);
         for (coordinates, _) in &self.sub_sectors_map {
@@ -109,10 +256,35 @@ impl Sector {
 
     pub fn missions_at(&self, x: i32, y: i32) -> Vec<&Mission> {
         let sub_sector = &self.sub_sectors[&self.sub_sectors_map[&Coordinates::new(x, y)]];
-        self.missions
+        if sub_sector.visibility == Visibility::Undiscovered {
+            return vec![];
+        }
+        let mut missions: Vec<&Mission> = self
+            .missions
             .iter()
             .filter(|m| m.sub_sector_id == *sub_sector.id())
-            .collect()
+            .collect();
+        // the storage iterates in random order, the board must not reshuffle between frames or saves
+        missions.sort_by_key(|m| (m.expires_at.unwrap_or(u32::MAX), m.id().clone()));
+        missions
+    }
+
+    pub fn sector_type_at(&self, x: i32, y: i32) -> SectorType {
+        let sub_sector = &self.sub_sectors[&self.sub_sectors_map[&Coordinates::new(x, y)]];
+        sub_sector.sector_type.clone()
+    }
+
+    /// distance from the outpost in the center of the sector
+    pub fn distance_to(&self, sub_sector_id: &String) -> i32 {
+        self.coordinates_of(sub_sector_id).hex_length()
+    }
+
+    fn coordinates_of(&self, sub_sector_id: &String) -> Coordinates {
+        self.sub_sectors_map
+            .iter()
+            .find(|(_, id)| *id == sub_sector_id)
+            .map(|(c, _)| *c)
+            .unwrap()
     }
 
     pub fn get_mission(&self, mission_id: &String) -> &Mission {
@@ -130,24 +302,80 @@ impl Sector {
             .collect()
     }
 
-    pub fn add_mission(&mut self, x: i32, y: i32, mission_type: MissionType) {
+    pub fn add_mission(
+        &mut self,
+        x: i32,
+        y: i32,
+        mission_type: MissionType,
+        expires_at: Option<u32>,
+    ) {
         let sub_sector = &self.sub_sectors[&self.sub_sectors_map[&Coordinates::new(x, y)]];
-        self.missions
-            .add(Mission::new(sub_sector.id().clone(), mission_type))
+        let mut mission = Mission::new(sub_sector.id().clone(), mission_type);
+        mission.expires_at = expires_at;
+        self.missions.add(mission)
+    }
+
+    /// crew members currently away on a mission
+    pub fn mission_crew_len(&self) -> usize {
+        self.active_mission
+            .as_ref()
+            .map(|a| a.crew_len())
+            .unwrap_or(0)
+    }
+
+    pub fn sub_sectors_len(&self) -> usize {
+        self.sub_sectors.len()
+    }
+
+    pub fn visited_sub_sectors_len(&self) -> usize {
+        self.sub_sectors
+            .iter()
+            .filter(|s| s.visibility == Visibility::Visited)
+            .count()
     }
 
     pub fn set_active_mission(&mut self, active_mission: ActiveMission) {
         self.active_mission = Some(active_mission)
     }
+
+    /// a returned mission is completed and leaves the board, its report remains
+    pub fn take_returned_mission(
+        &mut self,
+        state: &GameState,
+    ) -> Option<(ActiveMission, MissionReport)> {
+        match &self.active_mission {
+            Some(a) if a.has_returned() => {
+                let mission = self.missions.remove(&a.mission_id).unwrap();
+                let coordinates = self.coordinates_of(&mission.sub_sector_id);
+                let report = a.report(&mission, coordinates, state.current_turn);
+                self.active_mission.take().map(|a| (a, report))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn pending_mission_event(&self) -> Option<&MissionEventKind> {
+        self.active_mission
+            .as_ref()
+            .and_then(|a| a.pending_event.as_ref())
+    }
+
+    pub fn resolve_mission_event(&mut self, state: &mut GameState, option_index: usize) {
+        if let Some(a) = self.active_mission.as_mut() {
+            a.resolve_event(state, option_index)
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct SubSector {
     pub sector_type: SectorType,
     id: String,
+    #[serde(default = "default_visibility")]
+    pub visibility: Visibility,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SectorType {
     EmptySpace,
     SolarSystem,
@@ -155,11 +383,48 @@ pub enum SectorType {
     StellarRift,
 }
 
+impl SectorType {
+    pub fn random_mission(&self, state: &mut GameState) -> MissionType {
+        use SectorType::*;
+        match self {
+            SolarSystem => {
+                let min = state.rng.gen_range(3..8);
+                MissionType::Mining(min, min + state.rng.gen_range(2..6))
+            }
+            EmptySpace => match state.rng.gen_range(0..3) {
+                0 => MissionType::Exploration,
+                1 => MissionType::Salvage(state.rng.gen_range(2..5)),
+                _ => MissionType::Rescue,
+            },
+            StellarRift => match state.rng.gen_range(0..2) {
+                0 => MissionType::Exploration,
+                _ => MissionType::Salvage(state.rng.gen_range(2..5)),
+            },
+            GasCloud => match state.rng.gen_range(0..2) {
+                0 => MissionType::Exploration,
+                _ => MissionType::Rescue,
+            },
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Visibility {
+    Undiscovered,
+    Discovered,
+    Visited,
+}
+
+fn default_visibility() -> Visibility {
+    Visibility::Discovered
+}
+
 impl SubSector {
     pub fn new(sector_type: SectorType) -> Self {
         Self {
             id: nanoid!(),
             sector_type,
+            visibility: Visibility::Undiscovered,
         }
     }
 }
@@ -175,6 +440,9 @@ pub struct Mission {
     pub id: String,
     pub sub_sector_id: String,
     pub mission_type: MissionType,
+    /// the turn the mission disappears from the board, missions without one stay forever
+    #[serde(default)]
+    pub expires_at: Option<u32>,
 }
 
 impl Mission {
@@ -183,8 +451,18 @@ impl Mission {
             id: nanoid!(),
             sub_sector_id,
             mission_type,
+            expires_at: None,
         }
     }
+
+    pub fn turns_left(&self, current_turn: u32) -> Option<u32> {
+        self.expires_at
+            .map(|expires_at| expires_at.saturating_sub(current_turn))
+    }
+
+    pub fn is_expired(&self, current_turn: u32) -> bool {
+        self.turns_left(current_turn) == Some(0)
+    }
 }
 
 impl Entity for Mission {
@@ -193,28 +471,97 @@ impl Entity for Mission {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum MissionType {
     Mining(u16, u16),
+    Exploration,
+    /// a wreck that takes the given number of turns to strip
+    Salvage(u16),
+    Rescue,
+}
+
+impl fmt::Display for MissionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use MissionType::*;
+        match *self {
+            Mining(_, _) => write!(f, "Mining Mission"),
+            Exploration => write!(f, "Exploration Mission"),
+            Salvage(_) => write!(f, "Salvage Mission"),
+            Rescue => write!(f, "Rescue Mission"),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct ActiveMission {
     pub mission_id: String,
     pub resources: Resources,
+    #[serde(default)]
+    pub supplies: Resources,
+    /// turns of travel in each direction
     pub distance: u16,
+    #[serde(default)]
+    pub ship: Option<Ship>,
+    #[serde(default)]
+    pub blueprints: u32,
     pub state: ActiveMissionState,
+    #[serde(default)]
+    pub pending_event: Option<MissionEventKind>,
     crew: Storage<CrewMember>,
+    #[serde(default)]
+    casualties: Vec<CrewMember>,
+    #[serde(default)]
+    turns: u32,
+    #[serde(default)]
+    gathered: Vec<Resources>,
+    #[serde(default)]
+    events: Vec<String>,
+    /// health of each crew member by id when the mission departed
+    #[serde(default)]
+    departure_health: HashMap<String, i32>,
 }
 
 impl ActiveMission {
-    pub fn new(mission_id: &String, resources: Resources, crew: Vec<CrewMember>) -> Self {
+    pub fn crew_len(&self) -> usize {
+        self.crew.len()
+    }
+
+    pub fn new(mission_id: &String, supplies: Resources, crew: Vec<CrewMember>) -> Self {
         Self {
             mission_id: mission_id.clone(),
-            resources,
+            resources: Resources::zero(),
+            supplies,
             distance: 0,
+            ship: None,
+            blueprints: 0,
             state: ActiveMissionState::OutwardTrip(0),
+            pending_event: None,
+            departure_health: crew.iter().map(|c| (c.id().clone(), c.health())).collect(),
             crew: Storage::from(crew),
+            casualties: vec![],
+            turns: 0,
+            gathered: vec![],
+            events: vec![],
+        }
+    }
+
+    pub fn report(&self, mission: &Mission, coordinates: Coordinates, turn: u32) -> MissionReport {
+        let health_change = |c: &CrewMember| CrewHealthChange {
+            name: c.name().clone(),
+            before: self.departure_health.get(c.id()).cloned(),
+            after: c.health(),
+        };
+        MissionReport {
+            title: mission.mission_type.to_string(),
+            x: coordinates.x,
+            y: coordinates.y,
+            returned_at: turn,
+            turns: self.turns,
+            gathered: self.gathered.clone(),
+            blueprints: self.blueprints,
+            events: self.events.clone(),
+            crew: self.crew.iter().map(health_change).collect(),
+            casualties: self.casualties.iter().map(|c| c.name().clone()).collect(),
         }
     }
 
@@ -222,26 +569,192 @@ impl ActiveMission {
         self.state = ActiveMissionState::ReturnTrip(0)
     }
 
-    pub fn finish_turn(&mut self, state: &mut GameState, mission: &Mission) {
-        use ActiveMissionState::*;
+    pub fn crew_stats(&self) -> Stats {
+        team_stats(self.crew.iter())
+    }
+
+    /// chance that something happens on site during the next turn
+    pub fn risk(&self, mission_type: &MissionType) -> f64 {
         match self.state {
-            AtDestination(turn) => {
-                match mission.mission_type {
-                    MissionType::Mining(min, max) => {
-                        self.resources += Resources::minerals(state.rng.gen_range(min..max).into())
+            ActiveMissionState::AtDestination(turn) => {
+                MissionEventKind::chance(mission_type, turn, self.crew_stats().engineering)
+            }
+            _ => 0.0,
+        }
+    }
+
+    pub fn has_arrived(&self) -> bool {
+        matches!(self.state, ActiveMissionState::AtDestination(0))
+    }
+
+    pub fn has_returned(&self) -> bool {
+        matches!(self.state, ActiveMissionState::Returned)
+    }
+
+    /// hands over the surviving crew and the ship together with the cargo and the leftover supplies
+    pub fn disband(self) -> MissionReturn {
+        MissionReturn {
+            crew: self.crew.iter().cloned().collect(),
+            resources: self.resources + self.supplies,
+            ship: self.ship,
+            blueprints: self.blueprints,
+        }
+    }
+
+    /// minerals beyond the cargo capacity of the ship are left behind
+    fn load_cargo(&mut self, resources: Resources) {
+        self.resources += resources;
+        if let Some(ship) = &self.ship {
+            self.resources[Minerals] =
+                std::cmp::min(self.resources[Minerals], ship.cargo_capacity());
+        }
+    }
+
+    pub fn is_cargo_full(&self) -> bool {
+        match &self.ship {
+            Some(ship) => self.resources[Minerals] >= ship.cargo_capacity(),
+            None => false,
+        }
+    }
+
+    /// true while the supplies last for one more turn on site and the trip home
+    fn has_supplies_for_return(&self) -> bool {
+        let upkeep = team_upkeep(self.crew.iter()).stocks();
+        self.supplies.covers(&(upkeep * (self.distance as i32 + 1)))
+    }
+
+    /// engineers get more out of a wreck and now and then recover a blueprint
+    fn salvage(&mut self, state: &mut GameState) {
+        let bonus = (100 + self.crew_stats().engineering) * state.config().mission_yield / 100;
+        let salvaged = Resources::minerals(state.rng.gen_range(1..4))
+            + Resources::food(state.rng.gen_range(0..3))
+            + Resources::water(state.rng.gen_range(0..3));
+        self.load_cargo(salvaged.map(|_, v| v * bonus / 100));
+        if state.rng.gen_bool(BLUEPRINT_CHANCE) {
+            state.log("the mission crew recovered a blueprint");
+            self.blueprints += 1;
+        }
+    }
+
+    /// a team with medical training keeps the survivor alive
+    fn rescue(&mut self, state: &mut GameState) {
+        if !state.rng.gen_bool(skill_chance(self.crew_stats().biology)) {
+            state.log("the mission crew found no survivors");
+            return;
+        }
+        let mut survivor = CrewMember::random(state);
+        survivor.injure(2);
+        survivor.assign_to_mission(&self.mission_id);
+        state.log(&format!("the mission crew rescued {}", survivor.name()));
+        self.crew.add(survivor);
+    }
+
+    /// every crew member lives off the supplies, they rest on board of the ship
+    fn support_crew(&mut self, state: &mut GameState) {
+        use ResourceType::*;
+        for c in self.crew.iter_mut() {
+            c.finish_turn();
+            if !c.is_alive() {
+                state.log(&format!("{} died on a mission", c.name()));
+                self.casualties.push(c.clone());
+                continue;
+            }
+
+            let upkeep = c.upkeep();
+            if self.supplies[Food] >= upkeep[Food] {
+                self.supplies[Food] -= upkeep[Food];
+                c.eat();
+            }
+            if self.supplies[Water] >= upkeep[Water] {
+                self.supplies[Water] -= upkeep[Water];
+                c.drink();
+            }
+            c.rest();
+        }
+        self.crew.retain(|c| c.is_alive());
+    }
+
+    pub fn resolve_event(&mut self, state: &mut GameState, option_index: usize) {
+        if let Some(event) = self.pending_event.take() {
+            let options = event.options();
+            // an unknown choice leaves the event pending
+            let option = match options.get(option_index) {
+                Some(option) => option,
+                None => {
+                    self.pending_event = Some(event);
+                    return;
+                }
+            };
+            let decision = format!("mission crew decided to {}", option.description);
+            state.log(&decision);
+            self.events.push(decision);
+            self.apply_outcomes(state, &option.outcomes);
+        }
+    }
+
+    fn apply_outcomes(&mut self, state: &mut GameState, outcomes: &Vec<MissionEventOutcome>) {
+        use MissionEventOutcome::*;
+        for outcome in outcomes {
+            match outcome {
+                Gain(resources) => self.load_cargo(resources.clone()),
+                Loss(resources) => self.resources -= resources.clone(),
+                Injury(amount) => {
+                    for c in self.crew.iter_mut() {
+                        c.injure(*amount);
+                        if !c.is_alive() {
+                            state.log(&format!("{} died on a mission", c.name()));
+                            self.casualties.push(c.clone());
+                        }
+                    }
+                    self.crew.retain(|c| c.is_alive());
+                }
+                Abort => self.start_return_trip(),
+                Fight(loot) => {
+                    if state.rng.gen_bool(skill_chance(self.crew_stats().military)) {
+                        state.log("the mission crew drove off the attackers");
+                        self.apply_outcomes(state, &vec![Gain(loot.clone())]);
+                    } else {
+                        state.log("the mission crew was overwhelmed");
+                        self.apply_outcomes(
+                            state,
+                            &vec![Injury(2), Loss(Resources::minerals(loot[Minerals]))],
+                        );
                     }
                 }
-                self.state = AtDestination(turn + 1);
+            }
+        }
+    }
+
+    pub fn finish_turn(
+        &mut self,
+        state: &mut GameState,
+        mission: &Mission,
+        sector_type: &SectorType,
+    ) {
+        use ActiveMissionState::*;
+        if self.has_returned() {
+            return;
+        }
+        self.turns += 1;
+        self.support_crew(state);
+        if self.pending_event.is_some() {
+            return;
+        }
+        match self.state {
+            AtDestination(turn) => {
+                let cargo = self.resources.clone();
+                self.work_on_site(state, mission, sector_type, turn);
+                self.gathered.push(self.resources.clone() - cargo);
             }
             OutwardTrip(turn) => {
-                if self.distance >= turn {
+                if turn + 1 >= self.distance {
                     self.state = AtDestination(0)
                 } else {
                     self.state = OutwardTrip(turn + 1);
                 }
             }
             ReturnTrip(turn) => {
-                if self.distance >= turn {
+                if turn + 1 >= self.distance {
                     self.state = Returned
                 } else {
                     self.state = ReturnTrip(turn + 1);
@@ -250,6 +763,137 @@ impl ActiveMission {
             Returned => (),
         }
     }
+
+    fn work_on_site(
+        &mut self,
+        state: &mut GameState,
+        mission: &Mission,
+        sector_type: &SectorType,
+        turn: u16,
+    ) {
+        let chance = self.risk(&mission.mission_type);
+        let event = MissionEventKind::draw(state, sector_type, &mission.mission_type, chance);
+        if let Some(event) = event {
+            state.log(event.description());
+            self.events.push(event.description().to_string());
+            if event.requires_decision() {
+                self.pending_event = Some(event);
+                return;
+            }
+            self.apply_outcomes(state, &event.options()[0].outcomes);
+        }
+
+        match mission.mission_type {
+            MissionType::Mining(min, max) => {
+                let roll = state.rng.gen_range(min..max);
+                let geology = self.crew_stats().geology;
+                let mined = mining_yield(roll, turn, geology) * state.config().mission_yield / 100;
+                self.load_cargo(Resources::minerals(mined));
+            }
+            MissionType::Exploration => {
+                self.start_return_trip();
+                return;
+            }
+            MissionType::Salvage(wrecks) => {
+                self.salvage(state);
+                if turn + 1 >= wrecks {
+                    state.log("the wreck is stripped, the mission crew heads home");
+                    self.start_return_trip();
+                    return;
+                }
+            }
+            MissionType::Rescue => {
+                self.rescue(state);
+                self.start_return_trip();
+                return;
+            }
+        }
+        if self.is_cargo_full() {
+            state.log("the cargo hold is full, the mission crew heads home");
+            self.start_return_trip();
+            return;
+        }
+        if !self.has_supplies_for_return() {
+            state.log("supplies are running low, the mission crew heads home");
+            self.start_return_trip();
+            return;
+        }
+        self.state = ActiveMissionState::AtDestination(turn + 1);
+    }
+}
+
+pub struct MissionReturn {
+    pub crew: Vec<CrewMember>,
+    pub resources: Resources,
+    pub ship: Option<Ship>,
+    pub blueprints: u32,
+}
+
+pub struct MissionForecast {
+    pub expected_yield: Resources,
+    pub success_chance: f64,
+    /// the chance to drive off attackers on the way
+    pub combat_chance: f64,
+}
+
+impl MissionType {
+    /// what a team with the given stats can expect from this mission
+    pub fn forecast(&self, team: &Stats, config: &DifficultyConfig) -> MissionForecast {
+        match *self {
+            MissionType::Mining(min, max) => {
+                let safe_days: f64 = (0..3)
+                    .map(|day| 1.0 - MissionEventKind::chance(self, day, team.engineering))
+                    .product();
+                MissionForecast {
+                    expected_yield: Resources::minerals(
+                        mining_yield((min + max) / 2, 0, team.geology) * config.mission_yield / 100,
+                    ),
+                    success_chance: safe_days,
+                    combat_chance: skill_chance(team.military),
+                }
+            }
+            // the explorers look around for a single day and head home
+            MissionType::Exploration => MissionForecast {
+                expected_yield: Resources::zero(),
+                success_chance: 1.0 - MissionEventKind::chance(self, 0, team.engineering),
+                combat_chance: skill_chance(team.military),
+            },
+            MissionType::Salvage(wrecks) => {
+                let bonus = (100 + team.engineering) * config.mission_yield / 100;
+                let safe_days: f64 = (0..wrecks)
+                    .map(|day| 1.0 - MissionEventKind::chance(self, day, team.engineering))
+                    .product();
+                MissionForecast {
+                    expected_yield: (Resources::minerals(2)
+                        + Resources::food(1)
+                        + Resources::water(1))
+                    .map(|_, v| v * bonus / 100),
+                    success_chance: safe_days,
+                    combat_chance: skill_chance(team.military),
+                }
+            }
+            MissionType::Rescue => MissionForecast {
+                expected_yield: Resources::zero(),
+                success_chance: skill_chance(team.biology),
+                combat_chance: skill_chance(team.military),
+            },
+        }
+    }
+
+    pub fn rewards(&self) -> &'static str {
+        match self {
+            MissionType::Mining(_, _) => "minerals, richer the longer the crew digs",
+            MissionType::Exploration => "reveals the neighbouring sub sectors and their missions",
+            MissionType::Salvage(_) => "minerals, food and water, sometimes a blueprint",
+            MissionType::Rescue => "a survivor who joins the outpost crew",
+        }
+    }
+}
+
+/// the deeper the crew digs the richer the veins, a trained geologist finds them faster
+pub fn mining_yield(roll: u16, days_on_site: u16, geology: i32) -> i32 {
+    let bonus = 100 + 10 * days_on_site as i32 + geology;
+    roll as i32 * bonus / 100
 }
 
 #[derive(Serialize, Deserialize)]
@@ -271,3 +915,347 @@ impl std::string::ToString for ActiveMissionState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        mining_yield, ActiveMission, ActiveMissionState, Coordinates, Mission, MissionType, Sector,
+        SectorType, SubSector, Visibility,
+    };
+    use crate::model::crew::CrewMember;
+    use crate::model::difficulty::Difficulty;
+    use crate::model::game_state::GameState;
+    use crate::model::mission_events::MissionEventKind;
+    use crate::model::resources::ResourceType;
+    use crate::model::resources::Resources;
+    use crate::model::ship::{Ship, ShipClass};
+    use crate::model::stats::Stats;
+    use crate::model::Entity;
+
+    fn sector() -> Sector {
+        let mut sector = Sector::new();
+        sector.add_subsector(0, 0, SubSector::new(SectorType::SolarSystem));
+        sector.add_subsector(1, 0, SubSector::new(SectorType::EmptySpace));
+        sector.add_subsector(2, 0, SubSector::new(SectorType::EmptySpace));
+        sector
+    }
+
+    fn visibility_at(sector: &Sector, x: i32, y: i32) -> &Visibility {
+        sector
+            .sub_sectors_map()
+            .iter()
+            .find(|(c, _)| c.x == x && c.y == y)
+            .map(|(_, s)| &s.visibility)
+            .unwrap()
+    }
+
+    #[test]
+    fn visit_reveals_neighbours() {
+        let mut sector = sector();
+
+        let discovered = sector.visit(0, 0);
+
+        assert_eq!(1, discovered.len());
+        assert!(*visibility_at(&sector, 0, 0) == Visibility::Visited);
+        assert!(*visibility_at(&sector, 1, 0) == Visibility::Discovered);
+        assert!(*visibility_at(&sector, 2, 0) == Visibility::Undiscovered);
+    }
+
+    #[test]
+    fn visit_reports_neighbours_in_order() {
+        let mut sector = sector();
+        for (x, y) in [(0, -1), (1, -1), (-1, 0), (-1, 1), (0, 1)] {
+            sector.add_subsector(x, y, SubSector::new(SectorType::EmptySpace));
+        }
+
+        let discovered = sector.visit(0, 0);
+
+        let mut sorted = discovered.clone();
+        sorted.sort();
+        assert_eq!(5, discovered.len());
+        assert!(discovered == sorted);
+    }
+
+    #[test]
+    fn undiscovered_missions_are_hidden() {
+        let mut sector = sector();
+        sector.add_mission(2, 0, MissionType::Exploration, None);
+        assert_eq!(0, sector.missions_at(2, 0).len());
+
+        sector.visit(1, 0);
+        assert_eq!(1, sector.missions_at(2, 0).len());
+    }
+
+    #[test]
+    fn missions_at_lists_the_closest_deadline_first() {
+        let mut sector = sector();
+        sector.visit(0, 0);
+        sector.add_mission(1, 0, MissionType::Exploration, None);
+        sector.add_mission(1, 0, MissionType::Rescue, Some(8));
+        sector.add_mission(1, 0, MissionType::Salvage(2), Some(4));
+
+        let deadlines: Vec<Option<u32>> = sector
+            .missions_at(1, 0)
+            .iter()
+            .map(|m| m.expires_at)
+            .collect();
+
+        assert_eq!(vec![Some(4), Some(8), None], deadlines);
+    }
+
+    #[test]
+    fn exploration_reveals_destination_neighbours() {
+        let mut sector = sector();
+        let mut state = GameState::new(0);
+        sector.visit(0, 0);
+        sector.add_mission(1, 0, MissionType::Exploration, None);
+
+        let sub_sector_id = sector.missions_at(1, 0)[0].sub_sector_id.clone();
+        sector.arrive_at(
+            &mut state,
+            &sub_sector_id,
+            &MissionType::Exploration,
+            &Stats::zero(),
+        );
+
+        assert!(*visibility_at(&sector, 1, 0) == Visibility::Visited);
+        assert!(*visibility_at(&sector, 2, 0) == Visibility::Discovered);
+    }
+
+    #[test]
+    fn other_missions_only_visit_their_destination() {
+        let mut sector = sector();
+        let mut state = GameState::new(0);
+        sector.visit(0, 0);
+        sector.add_mission(1, 0, MissionType::Mining(5, 10), None);
+
+        let sub_sector_id = sector.missions_at(1, 0)[0].sub_sector_id.clone();
+        sector.arrive_at(
+            &mut state,
+            &sub_sector_id,
+            &MissionType::Mining(5, 10),
+            &Stats::zero(),
+        );
+
+        assert!(*visibility_at(&sector, 1, 0) == Visibility::Visited);
+        assert!(*visibility_at(&sector, 2, 0) == Visibility::Undiscovered);
+        assert!(state.logs.is_empty());
+    }
+
+    #[test]
+    fn exploration_succeeds_without_an_event_on_site() {
+        let config = Difficulty::Normal.config();
+        let forecast = MissionType::Exploration.forecast(&Stats::zero(), &config);
+        assert_eq!(
+            1.0 - MissionEventKind::chance(&MissionType::Exploration, 0, 0),
+            forecast.success_chance
+        );
+
+        let engineers = MissionType::Exploration.forecast(&Stats::engineering(100), &config);
+        let astrophysicists = MissionType::Exploration.forecast(&Stats::astrophysics(100), &config);
+        assert!(engineers.success_chance > forecast.success_chance);
+        assert_eq!(forecast.success_chance, astrophysicists.success_chance);
+    }
+
+    #[test]
+    fn unknown_event_options_keep_the_event_pending() {
+        let mut state = GameState::new(0);
+        let mission = Mission::new("sub_sector".to_string(), MissionType::Mining(5, 10));
+        let mut active_mission = ActiveMission::new(mission.id(), Resources::zero(), vec![]);
+        active_mission.pending_event = Some(MissionEventKind::GasLeak);
+
+        active_mission.resolve_event(&mut state, 9);
+
+        assert!(active_mission.pending_event == Some(MissionEventKind::GasLeak));
+        assert!(state.logs.is_empty());
+    }
+
+    #[test]
+    fn pending_event_pauses_mission() {
+        let mut state = GameState::new(0);
+        let mission = Mission::new("sub_sector".to_string(), MissionType::Mining(5, 10));
+        let mut active_mission = ActiveMission::new(mission.id(), Resources::zero(), vec![]);
+        active_mission.state = ActiveMissionState::AtDestination(2);
+        active_mission.pending_event = Some(MissionEventKind::GasLeak);
+
+        active_mission.finish_turn(&mut state, &mission, &SectorType::GasCloud);
+
+        assert!(matches!(
+            active_mission.state,
+            ActiveMissionState::AtDestination(2)
+        ));
+        assert_eq!(Resources::zero(), active_mission.resources);
+    }
+
+    #[test]
+    fn resolving_an_event_applies_its_outcomes() {
+        let mut state = GameState::new(0);
+        let mission = Mission::new("sub_sector".to_string(), MissionType::Mining(5, 10));
+        let crew = vec![CrewMember::new("a".to_string())];
+        let mut active_mission = ActiveMission::new(mission.id(), Resources::zero(), crew);
+        active_mission.state = ActiveMissionState::AtDestination(2);
+
+        active_mission.pending_event = Some(MissionEventKind::Anomaly);
+        active_mission.resolve_event(&mut state, 0);
+        assert!(active_mission.pending_event.is_none());
+        assert_eq!(Resources::minerals(8), active_mission.resources);
+        assert_eq!(80, active_mission.crew.iter().next().unwrap().health());
+
+        active_mission.pending_event = Some(MissionEventKind::GasLeak);
+        active_mission.resolve_event(&mut state, 1);
+        assert!(matches!(
+            active_mission.state,
+            ActiveMissionState::ReturnTrip(0)
+        ));
+    }
+
+    #[test]
+    fn mission_crew_lives_off_supplies() {
+        let mut state = GameState::new(0);
+        let mission = Mission::new("sub_sector".to_string(), MissionType::Exploration);
+        let crew = vec![CrewMember::new("a".to_string())];
+        let supplies = Resources::food(1) + Resources::water(1);
+        let mut active_mission = ActiveMission::new(mission.id(), supplies, crew);
+        active_mission.distance = 10;
+
+        active_mission.finish_turn(&mut state, &mission, &SectorType::EmptySpace);
+        assert!(active_mission.supplies.is_zero());
+
+        active_mission.finish_turn(&mut state, &mission, &SectorType::EmptySpace);
+        active_mission.finish_turn(&mut state, &mission, &SectorType::EmptySpace);
+        assert_eq!(60, active_mission.crew.iter().next().unwrap().health());
+    }
+
+    #[test]
+    fn cargo_capacity_caps_minerals() {
+        let mission = Mission::new("sub_sector".to_string(), MissionType::Mining(5, 10));
+        let mut active_mission = ActiveMission::new(mission.id(), Resources::zero(), vec![]);
+        active_mission.load_cargo(Resources::minerals(100));
+        assert!(!active_mission.is_cargo_full());
+
+        active_mission.resources = Resources::zero();
+        active_mission.ship = Some(Ship::new("shuttle", ShipClass::Shuttle));
+        active_mission.load_cargo(Resources::minerals(100) + Resources::food(3));
+        assert_eq!(
+            Resources::minerals(15) + Resources::food(3),
+            active_mission.resources
+        );
+        assert!(active_mission.is_cargo_full());
+    }
+
+    #[test]
+    fn salvage_yields_mixed_resources() {
+        let mut state = GameState::new(0);
+        let mission = Mission::new("sub_sector".to_string(), MissionType::Salvage(3));
+        let mut active_mission = ActiveMission::new(mission.id(), Resources::zero(), vec![]);
+
+        for _ in 0..10 {
+            active_mission.salvage(&mut state);
+        }
+
+        assert!(active_mission.resources[ResourceType::Minerals] >= 10);
+        assert!(active_mission.resources[ResourceType::Food] > 0);
+        assert!(active_mission.resources[ResourceType::Water] > 0);
+    }
+
+    #[test]
+    fn rescue_brings_home_a_survivor() {
+        let mut state = GameState::new(0);
+        let mission = Mission::new("sub_sector".to_string(), MissionType::Rescue);
+        let mut medic = CrewMember::new("medic".to_string());
+        medic.stats = Stats::biology(100);
+
+        let mut rescued = 0;
+        for _ in 0..10 {
+            let mut active_mission =
+                ActiveMission::new(mission.id(), Resources::zero(), vec![medic.clone()]);
+            active_mission.rescue(&mut state);
+            let returned = active_mission.disband();
+            for survivor in returned.crew.iter().filter(|c| c.name() != "medic") {
+                assert_eq!(&Some(mission.id().clone()), survivor.assigned_mission());
+                assert_eq!(60, survivor.health());
+                rescued += 1;
+            }
+        }
+        assert!(rescued >= 5);
+    }
+
+    #[test]
+    fn missions_expire() {
+        let mut sector = sector();
+        let mut state = GameState::new(0);
+        sector.visit(0, 0);
+        sector.add_mission(1, 0, MissionType::Exploration, Some(2));
+        sector.add_mission(0, 0, MissionType::Exploration, None);
+
+        state.current_turn = 1;
+        sector.remove_expired_missions(&mut state);
+        assert_eq!(1, sector.missions_at(1, 0)[0].turns_left(1).unwrap());
+
+        state.current_turn = 2;
+        sector.remove_expired_missions(&mut state);
+        assert_eq!(0, sector.missions_at(1, 0).len());
+        assert_eq!(1, sector.missions_at(0, 0).len());
+    }
+
+    #[test]
+    fn missions_spawn_on_known_sub_sectors() {
+        let mut sector = sector();
+        let mut state = GameState::new(0);
+        sector.visit(0, 0);
+
+        for _ in 0..100 {
+            sector.spawn_missions(&mut state);
+        }
+
+        assert_eq!(0, sector.missions_at(0, 0).len());
+        assert_eq!(1, sector.missions_at(1, 0).len());
+        assert_eq!(1, sector.missions.len());
+        assert!(sector.missions_at(1, 0)[0].expires_at.is_some());
+    }
+
+    #[test]
+    fn returned_missions_leave_the_board() {
+        let mut sector = sector();
+        sector.visit(0, 0);
+        sector.add_mission(1, 0, MissionType::Exploration, None);
+        let mission_id = sector.missions_at(1, 0)[0].id().clone();
+
+        let mut active_mission = ActiveMission::new(&mission_id, Resources::zero(), vec![]);
+        active_mission.state = ActiveMissionState::Returned;
+        sector.set_active_mission(active_mission);
+
+        let (_, report) = sector.take_returned_mission(&GameState::new(0)).unwrap();
+        assert_eq!("Exploration Mission", report.title);
+        assert_eq!((1, 0), (report.x, report.y));
+        assert_eq!(0, sector.missions_at(1, 0).len());
+    }
+
+    #[test]
+    fn report_summarises_the_mission() {
+        let mut state = GameState::new(0);
+        let mission = Mission::new("sub_sector".to_string(), MissionType::Mining(5, 10));
+        let crew = vec![CrewMember::new("a".to_string())];
+        let mut active_mission = ActiveMission::new(mission.id(), Resources::zero(), crew);
+        active_mission.state = ActiveMissionState::AtDestination(0);
+        active_mission.pending_event = Some(MissionEventKind::Anomaly);
+        active_mission.resolve_event(&mut state, 0);
+
+        let report = active_mission.report(&mission, Coordinates::new(0, -1), 7);
+
+        assert_eq!("Mining Mission", report.title);
+        assert_eq!(7, report.returned_at);
+        assert_eq!(vec!["mission crew decided to investigate"], report.events);
+        assert_eq!(Some(100), report.crew[0].before);
+        assert_eq!(80, report.crew[0].after);
+        assert!(report.casualties.is_empty());
+    }
+
+    #[test]
+    fn mining_yield_grows_with_days_and_geology() {
+        assert_eq!(5, mining_yield(5, 0, 0));
+        assert_eq!(7, mining_yield(5, 5, 0));
+        assert_eq!(10, mining_yield(5, 0, 100));
+        assert_eq!(12, mining_yield(5, 5, 100));
+    }
+}
@@ -0,0 +1,112 @@
+use nanoid::nanoid;
+use serde::{Deserialize, Serialize};
+
+use super::resources::Resources;
+use super::Entity;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShipClass {
+    Shuttle,
+    Freighter,
+}
+
+pub struct ShipClassMetadata {
+    pub name: &'static str,
+    pub crew_capacity: usize,
+    pub cargo_capacity: i32,
+    /// sub sectors travelled per turn
+    pub speed: i32,
+    /// minerals burnt per turn of travel
+    pub fuel_use: i32,
+    pub build_cost: Resources,
+}
+
+impl ShipClass {
+    pub const ALL: [ShipClass; 2] = [ShipClass::Shuttle, ShipClass::Freighter];
+
+    pub fn metadata(&self) -> ShipClassMetadata {
+        use ShipClass::*;
+        match self {
+            Shuttle => ShipClassMetadata {
+                name: "shuttle",
+                crew_capacity: 2,
+                cargo_capacity: 15,
+                speed: 2,
+                fuel_use: 1,
+                build_cost: Resources::minerals(10),
+            },
+            Freighter => ShipClassMetadata {
+                name: "freighter",
+                crew_capacity: 4,
+                cargo_capacity: 40,
+                speed: 1,
+                fuel_use: 2,
+                build_cost: Resources::minerals(25),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Ship {
+    id: String,
+    name: String,
+    pub class: ShipClass,
+}
+
+impl Ship {
+    pub fn new(name: &str, class: ShipClass) -> Self {
+        Self {
+            id: nanoid!(),
+            name: name.to_string(),
+            class,
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn crew_capacity(&self) -> usize {
+        self.class.metadata().crew_capacity
+    }
+
+    pub fn cargo_capacity(&self) -> i32 {
+        self.class.metadata().cargo_capacity
+    }
+
+    /// turns needed to cover the distance in one direction
+    pub fn travel_turns(&self, distance: i32) -> u16 {
+        let speed = self.class.metadata().speed;
+        ((distance + speed - 1) / speed) as u16
+    }
+
+    /// the fuel for the trip there and back
+    pub fn fuel(&self, distance: i32) -> Resources {
+        Resources::minerals(2 * self.class.metadata().fuel_use * self.travel_turns(distance) as i32)
+    }
+}
+
+impl Entity for Ship {
+    fn id(&self) -> &String {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ship, ShipClass};
+    use crate::model::resources::Resources;
+
+    #[test]
+    fn speed_shortens_travel() {
+        let shuttle = Ship::new("shuttle", ShipClass::Shuttle);
+        let freighter = Ship::new("freighter", ShipClass::Freighter);
+
+        assert_eq!(0, shuttle.travel_turns(0));
+        assert_eq!(1, shuttle.travel_turns(1));
+        assert_eq!(2, shuttle.travel_turns(3));
+        assert_eq!(3, freighter.travel_turns(3));
+        assert_eq!(Resources::minerals(12), freighter.fuel(3));
+    }
+}
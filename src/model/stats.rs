@@ -2,7 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::ops::Add;
 use std::ops::AddAssign;
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Stats {
     pub biology: i32,
     pub chemistry: i32,
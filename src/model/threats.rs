@@ -0,0 +1,228 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::game_state::GameState;
+use super::resources::Resources;
+use super::sector::SectorType;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThreatKind {
+    AsteroidShower,
+    PowerOutage,
+    Spoilage,
+    Leak,
+    Infection,
+    Raiders,
+}
+
+/// shields and guards keep at most three out of four crises and blows away
+pub const MAX_PROTECTION: i32 = 75;
+
+#[derive(Clone)]
+pub enum ThreatOutcome {
+    /// a random running module loses the given condition
+    DamageModule(i32),
+    Loss(Resources),
+    DrainBatteries,
+    /// injures one random crew member
+    Injury(i32),
+    /// injures the whole crew
+    Outbreak(i32),
+}
+
+pub struct ThreatOption {
+    pub description: &'static str,
+    pub outcomes: Vec<ThreatOutcome>,
+}
+
+impl ThreatKind {
+    pub const ALL: [ThreatKind; 6] = [
+        ThreatKind::AsteroidShower,
+        ThreatKind::PowerOutage,
+        ThreatKind::Spoilage,
+        ThreatKind::Leak,
+        ThreatKind::Infection,
+        ThreatKind::Raiders,
+    ];
+
+    /// the chance of a crisis each turn, the base chance depends on the difficulty
+    pub fn chance(base: f64, sector_type: &SectorType, shielding: i32) -> f64 {
+        let protection = shielding.clamp(0, MAX_PROTECTION) as f64 / 100.0;
+        (base * ThreatKind::danger(sector_type) * (1.0 - protection)).min(1.0)
+    }
+
+    /// how much rougher than a solar system the surroundings of the outpost are
+    fn danger(sector_type: &SectorType) -> f64 {
+        use SectorType::*;
+        match sector_type {
+            EmptySpace => 0.75,
+            SolarSystem => 1.0,
+            GasCloud => 1.25,
+            StellarRift => 1.5,
+        }
+    }
+
+    pub fn draw(
+        state: &mut GameState,
+        sector_type: &SectorType,
+        shielding: i32,
+    ) -> Option<ThreatKind> {
+        let chance = ThreatKind::chance(state.config().threat_chance, sector_type, shielding);
+        if !state.rng.gen_bool(chance) {
+            return None;
+        }
+        ThreatKind::ALL
+            .choose_weighted(&mut state.rng, |k| k.weight(sector_type))
+            .ok()
+            .cloned()
+    }
+
+    /// where the outpost is located decides what it has to fear
+    fn weight(&self, sector_type: &SectorType) -> u32 {
+        use SectorType::*;
+        use ThreatKind::*;
+        match (self, sector_type) {
+            (AsteroidShower, SolarSystem) => 4,
+            (AsteroidShower, _) => 2,
+            (PowerOutage, StellarRift) => 4,
+            (PowerOutage, _) => 2,
+            (Spoilage, _) => 2,
+            (Leak, GasCloud) => 3,
+            (Leak, _) => 2,
+            (Infection, GasCloud) => 3,
+            (Infection, _) => 1,
+            (Raiders, EmptySpace) => 3,
+            (Raiders, _) => 1,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        use ThreatKind::*;
+        match self {
+            AsteroidShower => "An asteroid shower hits the outpost.",
+            PowerOutage => "A power surge trips the grid and drains the batteries.",
+            Spoilage => "Mould spreads through the food stores.",
+            Leak => "A pipe bursts in the water storage.",
+            Infection => "An infection breaks out among the crew.",
+            Raiders => "Raiders demand a share of the outpost's minerals.",
+        }
+    }
+
+    /// threats with a single option strike immediately, severe ones wait for a response
+    pub fn options(&self) -> Vec<ThreatOption> {
+        use ThreatKind::*;
+        use ThreatOutcome::*;
+        match self {
+            AsteroidShower => vec![ThreatOption {
+                description: "repair what is left",
+                outcomes: vec![DamageModule(30)],
+            }],
+            PowerOutage => vec![ThreatOption {
+                description: "reboot the grid",
+                outcomes: vec![DrainBatteries],
+            }],
+            Spoilage => vec![ThreatOption {
+                description: "throw away the spoiled food",
+                outcomes: vec![Loss(Resources::food(5))],
+            }],
+            Leak => vec![ThreatOption {
+                description: "seal the pipe",
+                outcomes: vec![Loss(Resources::water(5))],
+            }],
+            Infection => vec![
+                ThreatOption {
+                    description: "quarantine the sick",
+                    outcomes: vec![Injury(2)],
+                },
+                ThreatOption {
+                    description: "carry on",
+                    outcomes: vec![Outbreak(1)],
+                },
+            ],
+            Raiders => vec![
+                ThreatOption {
+                    description: "pay them off",
+                    outcomes: vec![Loss(Resources::minerals(10))],
+                },
+                ThreatOption {
+                    description: "refuse",
+                    outcomes: vec![DamageModule(20), Injury(1)],
+                },
+            ],
+        }
+    }
+
+    pub fn requires_decision(&self) -> bool {
+        self.options().len() > 1
+    }
+
+    /// hostile threats can be fought off by the outpost's defenders
+    pub fn is_hostile(&self) -> bool {
+        matches!(self, ThreatKind::Raiders)
+    }
+}
+
+impl ThreatOutcome {
+    /// the outcome after the defenders fought back, none if they prevented it entirely
+    pub fn defended(self, defense: i32) -> Option<ThreatOutcome> {
+        use ThreatOutcome::*;
+        let protection = defense.clamp(0, MAX_PROTECTION);
+        let reduce = |amount: i32| amount * (100 - protection) / 100;
+        match self {
+            DamageModule(amount) => Some(DamageModule(reduce(amount))),
+            Injury(amount) => Some(Injury(reduce(amount))),
+            Outbreak(amount) => Some(Outbreak(reduce(amount))),
+            other => Some(other),
+        }
+        .filter(|outcome| !matches!(outcome, DamageModule(0) | Injury(0) | Outbreak(0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ThreatKind, ThreatOutcome};
+    use crate::model::sector::SectorType;
+
+    #[test]
+    fn shields_reduce_threat_chance() {
+        let home = SectorType::SolarSystem;
+        assert_eq!(0.15, ThreatKind::chance(0.15, &home, 0));
+        assert!(ThreatKind::chance(0.15, &home, 30) < ThreatKind::chance(0.15, &home, 0));
+        assert_eq!(
+            ThreatKind::chance(0.15, &home, 75),
+            ThreatKind::chance(0.15, &home, 200)
+        );
+    }
+
+    #[test]
+    fn rough_sectors_raise_threat_chance() {
+        assert!(
+            ThreatKind::chance(0.15, &SectorType::StellarRift, 0)
+                > ThreatKind::chance(0.15, &SectorType::EmptySpace, 0)
+        );
+        assert!(
+            ThreatKind::chance(0.15, &SectorType::StellarRift, 30)
+                > ThreatKind::chance(0.15, &SectorType::EmptySpace, 30)
+        );
+    }
+
+    #[test]
+    fn defense_reduces_damage() {
+        use ThreatOutcome::*;
+        assert!(matches!(
+            DamageModule(20).defended(0),
+            Some(DamageModule(20))
+        ));
+        assert!(matches!(
+            DamageModule(20).defended(50),
+            Some(DamageModule(10))
+        ));
+        assert!(matches!(
+            DamageModule(20).defended(500),
+            Some(DamageModule(5))
+        ));
+        assert!(Injury(1).defended(50).is_none());
+        assert!(matches!(DrainBatteries.defended(100), Some(DrainBatteries)));
+    }
+}